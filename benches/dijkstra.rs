@@ -0,0 +1,152 @@
+// compares priority queues in Dijkstra's algorithm on a random sparse graph.
+// run with: cargo bench --bench dijkstra
+//
+// queues:
+//     Heap:          lazy deletion, a vertex is inserted again instead of decreasing its key
+//                    and stale entries are skipped when removed;
+//     IndexMinPQ:    decrease_key on a binary heap, O(logn);
+//     FibonacciHeap: decrease_key in amortized O(1);
+//     PairingHeap:   decrease_key by cutting and melding the subtree.
+
+use std::time::{Duration, Instant};
+
+use rand::{Rng, SeedableRng, StdRng};
+
+use algorithms_in_rust::heap::binary_heap::Heap;
+use algorithms_in_rust::heap::fibonacci_heap::FibonacciHeap;
+use algorithms_in_rust::heap::index_heap::IndexMinPQ;
+use algorithms_in_rust::heap::order::Min;
+use algorithms_in_rust::heap::pairing_heap::PairingHeap;
+use algorithms_in_rust::heap::Handle;
+
+const VERTICES: usize = 200_000;
+const EDGES: usize = 2_000_000;
+const ROUNDS: u32 = 5;
+
+type Graph = Vec<Vec<(usize, u64)>>;
+
+// the minimum over several rounds is the least noisy estimate
+fn bench<F: FnMut() -> Vec<u64>>(name: &str, expected: &[u64], mut f: F) {
+    let mut best = Duration::from_secs(u64::MAX);
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let dist = f();
+        best = best.min(start.elapsed());
+        assert_eq!(dist, expected, "{} computed different distances", name);
+    }
+    println!("{:<14} {:>10.2?}", name, best);
+}
+
+fn lazy(g: &Graph, s: usize) -> Vec<u64> {
+    let mut dist = vec![u64::MAX; g.len()];
+    let mut pq = Heap::new_min();
+    dist[s] = 0;
+    pq.insert((0, s));
+
+    while let Some((d, v)) = pq.remove() {
+        if d > dist[v] {
+            continue;
+        }
+        for &(w, weight) in &g[v] {
+            if d + weight < dist[w] {
+                dist[w] = d + weight;
+                pq.insert((dist[w], w));
+            }
+        }
+    }
+    dist
+}
+
+fn indexed(g: &Graph, s: usize) -> Vec<u64> {
+    let mut dist = vec![u64::MAX; g.len()];
+    let mut pq = IndexMinPQ::new(g.len());
+    dist[s] = 0;
+    pq.insert(s, 0);
+
+    while let Some((v, d)) = pq.remove() {
+        for &(w, weight) in &g[v] {
+            if d + weight < dist[w] {
+                dist[w] = d + weight;
+                if pq.contains(w) {
+                    pq.decrease_key(w, dist[w]);
+                } else {
+                    pq.insert(w, dist[w]);
+                }
+            }
+        }
+    }
+    dist
+}
+
+// the operations Dijkstra needs from a heap with handles
+trait Addressable {
+    fn insert(&mut self, entry: (u64, usize)) -> Handle;
+    fn remove(&mut self) -> Option<(u64, usize)>;
+    fn decrease_key(&mut self, handle: Handle, entry: (u64, usize));
+}
+
+impl Addressable for FibonacciHeap<(u64, usize), Min> {
+    fn insert(&mut self, entry: (u64, usize)) -> Handle {
+        FibonacciHeap::insert(self, entry)
+    }
+
+    fn remove(&mut self) -> Option<(u64, usize)> {
+        FibonacciHeap::remove(self)
+    }
+
+    fn decrease_key(&mut self, handle: Handle, entry: (u64, usize)) {
+        FibonacciHeap::decrease_key(self, handle, entry);
+    }
+}
+
+impl Addressable for PairingHeap<(u64, usize), Min> {
+    fn insert(&mut self, entry: (u64, usize)) -> Handle {
+        PairingHeap::insert(self, entry)
+    }
+
+    fn remove(&mut self) -> Option<(u64, usize)> {
+        PairingHeap::remove(self)
+    }
+
+    fn decrease_key(&mut self, handle: Handle, entry: (u64, usize)) {
+        PairingHeap::decrease_key(self, handle, entry);
+    }
+}
+
+fn addressable<H: Addressable>(mut pq: H, g: &Graph, s: usize) -> Vec<u64> {
+    let mut dist = vec![u64::MAX; g.len()];
+    let mut handles: Vec<Option<Handle>> = vec![None; g.len()];
+    let mut done = vec![false; g.len()];
+    dist[s] = 0;
+    handles[s] = Some(pq.insert((0, s)));
+
+    while let Some((d, v)) = pq.remove() {
+        done[v] = true;
+        for &(w, weight) in &g[v] {
+            if !done[w] && d + weight < dist[w] {
+                dist[w] = d + weight;
+                match handles[w] {
+                    Some(h) => pq.decrease_key(h, (dist[w], w)),
+                    None => handles[w] = Some(pq.insert((dist[w], w))),
+                }
+            }
+        }
+    }
+    dist
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut g: Graph = vec![Vec::new(); VERTICES];
+    for _ in 0..EDGES {
+        let v = rng.gen_range(0, VERTICES);
+        let w = rng.gen_range(0, VERTICES);
+        g[v].push((w, rng.gen_range(0, 1000)));
+    }
+
+    let expected = lazy(&g, 0);
+    bench("Heap", &expected, || lazy(&g, 0));
+    bench("IndexMinPQ", &expected, || indexed(&g, 0));
+    bench("FibonacciHeap", &expected, || addressable(FibonacciHeap::new_min(), &g, 0));
+    bench("PairingHeap", &expected, || addressable(PairingHeap::new_min(), &g, 0));
+}
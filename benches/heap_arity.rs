@@ -0,0 +1,64 @@
+// compares d-ary heaps of different arities, 2 being the binary Heap.
+// run with: cargo bench --bench heap_arity
+//
+// workloads:
+//     push-heavy: many inserts, few removes, e.g. lazy Dijkstra which inserts a new entry
+//                 instead of decreasing a key(d-ary heaps should win);
+//     pop-heavy:  insert everything, then remove everything, i.e. heap sort(binary heaps
+//                 do fewer comparisons per level, wider heaps have fewer levels).
+
+use std::time::{Duration, Instant};
+
+use rand::{Rng, SeedableRng, StdRng};
+
+use algorithms_in_rust::heap::dary_heap::DaryHeap;
+use algorithms_in_rust::heap::order::Min;
+
+const N: usize = 1_000_000;
+const ROUNDS: u32 = 5;
+
+// the minimum over several rounds is the least noisy estimate
+fn bench<F: FnMut()>(name: &str, workload: &str, mut f: F) {
+    let mut best = Duration::from_secs(u64::MAX);
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        f();
+        best = best.min(start.elapsed());
+    }
+    println!("{:<12} {:<12} {:>10.2?}", workload, name, best);
+}
+
+macro_rules! bench_dary {
+    ($workload:ident, $keys:expr, $($d:literal),*) => {
+        $(bench(&format!("{}-ary", $d), stringify!($workload), || {
+            $workload(DaryHeap::<u64, $d, Min>::new_min(), $keys, |h, x| h.insert(x), |h| h.remove())
+        });)*
+    };
+}
+
+fn push_heavy<H>(mut h: H, keys: &[u64], insert: impl Fn(&mut H, u64), remove: impl Fn(&mut H) -> Option<u64>) {
+    // remove one element for every 4 inserted, with keys growing like tentative distances do
+    let mut last = 0;
+    for (i, &k) in keys.iter().enumerate() {
+        insert(&mut h, last + k);
+        if i % 4 == 3 {
+            last = remove(&mut h).unwrap();
+        }
+    }
+}
+
+fn pop_heavy<H>(mut h: H, keys: &[u64], insert: impl Fn(&mut H, u64), remove: impl Fn(&mut H) -> Option<u64>) {
+    for &k in keys {
+        insert(&mut h, k);
+    }
+    while remove(&mut h).is_some() {}
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let keys: Vec<u64> = (0..N).map(|_| rng.gen_range(0, 1_000_000)).collect();
+
+    bench_dary!(push_heavy, &keys, 2, 4, 8, 16);
+
+    bench_dary!(pop_heavy, &keys, 2, 4, 8, 16);
+}
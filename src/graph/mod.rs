@@ -0,0 +1,3 @@
+pub mod mst;
+pub mod shortest_path;
+pub mod weighted_graph;
@@ -0,0 +1,165 @@
+use crate::heap::binary_heap::Heap;
+use crate::heap::index_heap::IndexMinPQ;
+use crate::heap::order::{ByKey, Reversed};
+
+use super::weighted_graph::{Edge, Graph};
+
+// minimum spanning trees with Prim's algorithm, which grows a tree from a vertex by
+// repeatedly adding the lightest edge that connects the tree to a vertex outside of it.
+// the graph is taken as undirected, i.e. every edge is expected in the adjacency lists of
+// both its endpoints(see Graph::add_undirected_edge). for a graph that is not connected,
+// the result is a minimum spanning forest with a tree per component.
+//
+// lazy: all edges leaving the tree go on a heap, and edges whose both endpoints have joined
+// the tree meanwhile are skipped when removed. O(mlogm) time, O(m) space.
+// eager: an indexed priority queue holds, for every vertex outside the tree, only the
+// lightest edge connecting it to the tree. O(mlogn) time, O(n) space.
+//
+// inspired by https://algs4.cs.princeton.edu/43mst/
+
+pub struct SpanningForest {
+    edges: Vec<Edge>,
+    weight: f64,
+}
+
+impl SpanningForest {
+    // the tree edges, each directed away from the vertex that joined the tree earlier
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn from_edges(edges: Vec<Edge>) -> SpanningForest {
+        let weight = edges.iter().map(|e| e.weight).sum();
+        SpanningForest { edges, weight }
+    }
+}
+
+pub fn lazy_prim(g: &Graph) -> SpanningForest {
+    let mut marked = vec![false; g.vertices()];
+    let mut edges = Vec::new();
+    // the lightest edge on top
+    let mut pq = Heap::with_order(Reversed(ByKey(|e: &Edge| e.weight)));
+
+    for root in 0..g.vertices() {
+        if marked[root] {
+            continue;
+        }
+
+        marked[root] = true;
+        pq.extend(g.adj(root).iter().cloned());
+        while let Some(e) = pq.remove() {
+            if marked[e.to] {
+                continue;
+            }
+            edges.push(e);
+            marked[e.to] = true;
+            pq.extend(g.adj(e.to).iter().filter(|x| !marked[x.to]).cloned());
+        }
+    }
+
+    SpanningForest::from_edges(edges)
+}
+
+pub fn eager_prim(g: &Graph) -> SpanningForest {
+    let mut marked = vec![false; g.vertices()];
+    // the lightest known edge connecting every vertex to the tree
+    let mut edge_to: Vec<Option<Edge>> = vec![None; g.vertices()];
+    let mut edges = Vec::new();
+    let mut pq = IndexMinPQ::new(g.vertices());
+
+    for root in 0..g.vertices() {
+        if marked[root] {
+            continue;
+        }
+
+        pq.insert(root, 0.0);
+        while let Some((v, _)) = pq.remove() {
+            marked[v] = true;
+            if let Some(e) = edge_to[v] {
+                edges.push(e);
+            }
+
+            for &e in g.adj(v) {
+                let w = e.to;
+                if marked[w] || matches!(edge_to[w], Some(x) if x.weight <= e.weight) {
+                    continue;
+                }
+                edge_to[w] = Some(e);
+                if pq.contains(w) {
+                    pq.decrease_key(w, e.weight);
+                } else {
+                    pq.insert(w, e.weight);
+                }
+            }
+        }
+    }
+
+    SpanningForest::from_edges(edges)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    // https://algs4.cs.princeton.edu/43mst/tinyEWG.txt
+    fn tiny_ewg() -> Graph {
+        Graph::from_undirected_edges(8, &[
+            (4, 5, 0.35), (4, 7, 0.37), (5, 7, 0.28), (0, 7, 0.16), (1, 5, 0.32),
+            (0, 4, 0.38), (2, 3, 0.17), (1, 7, 0.19), (0, 2, 0.26), (1, 2, 0.36),
+            (1, 3, 0.29), (2, 7, 0.34), (6, 2, 0.40), (3, 6, 0.52), (6, 0, 0.58),
+            (6, 4, 0.93),
+        ])
+    }
+
+    fn normalized(f: &SpanningForest) -> Vec<(usize, usize)> {
+        let mut edges: Vec<_> = f.edges().iter().map(|e| (e.from.min(e.to), e.from.max(e.to))).collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_tiny() {
+        let expected = vec![(0, 2), (0, 7), (1, 7), (2, 3), (2, 6), (4, 5), (5, 7)];
+        for mst in [lazy_prim(&tiny_ewg()), eager_prim(&tiny_ewg())].iter() {
+            assert!((mst.weight() - 1.81).abs() < 1e-9);
+            assert_eq!(normalized(mst), expected);
+        }
+    }
+
+    #[test]
+    fn test_forest() {
+        let g = Graph::from_undirected_edges(5, &[(0, 1, 1.0), (1, 2, 2.0), (0, 2, 0.5), (3, 4, 4.0)]);
+        for forest in [lazy_prim(&g), eager_prim(&g)].iter() {
+            assert_eq!(normalized(forest), vec![(0, 1), (0, 2), (3, 4)]);
+            assert_eq!(forest.weight(), 5.5);
+        }
+        assert!(lazy_prim(&Graph::new(3)).edges().is_empty());
+    }
+
+    #[test]
+    fn test_lazy_and_eager_agree() {
+        let mut gen = rand::thread_rng();
+        for _ in 0..20 {
+            let n = gen.gen_range(1, 60);
+            let mut g = Graph::new(n);
+            for _ in 0..gen.gen_range(0, 4 * n) {
+                g.add_undirected_edge(gen.gen_range(0, n), gen.gen_range(0, n), gen.gen_range(0.0, 10.0));
+            }
+
+            let lazy = lazy_prim(&g);
+            let eager = eager_prim(&g);
+            assert_eq!(lazy.edges().len(), eager.edges().len());
+            assert!((lazy.weight() - eager.weight()).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::heap::index_heap::IndexMinPQ;
+
+use super::weighted_graph::{Edge, Graph};
+
+// single source shortest paths in graphs with nonnegative edge weights.
+//
+// Dijkstra's algorithm settles the vertices in order of their distance from the source,
+// using an indexed priority queue to decrease the tentative distance of a vertex in place:
+// O(mlogn) for n vertices and m edges.
+//
+// A* searches for a single target, ordering the vertices by distance from the source plus
+// a heuristic estimate of the distance to the target. with a heuristic that never
+// overestimates(admissible) the path found is a shortest one; the better the estimate,
+// the fewer vertices are explored. a heuristic of 0 makes A* Dijkstra's algorithm.
+//
+// inspired by https://algs4.cs.princeton.edu/44sp/DijkstraSP.java.html
+
+// the shortest paths from a source to all vertices
+pub struct ShortestPaths {
+    source: usize,
+    dist: Vec<f64>,
+    // the last edge on the shortest path to every vertex
+    edge_to: Vec<Option<Edge>>,
+}
+
+impl ShortestPaths {
+    pub fn source(&self) -> usize {
+        self.source
+    }
+
+    // the length of the shortest path to v, None if v is unreachable
+    pub fn dist_to(&self, v: usize) -> Option<f64> {
+        if self.dist[v].is_finite() {
+            Some(self.dist[v])
+        } else {
+            None
+        }
+    }
+
+    pub fn has_path_to(&self, v: usize) -> bool {
+        self.dist[v].is_finite()
+    }
+
+    // the edges of the shortest path from the source to v, None if v is unreachable
+    pub fn path_to(&self, v: usize) -> Option<Vec<Edge>> {
+        if !self.has_path_to(v) {
+            return None;
+        }
+        Some(path(&self.edge_to, v))
+    }
+}
+
+// follow edge_to back from v, the path ends at a vertex without an edge to it
+fn path(edge_to: &[Option<Edge>], v: usize) -> Vec<Edge> {
+    let mut path = Vec::new();
+    let mut x = v;
+    while let Some(e) = edge_to[x] {
+        path.push(e);
+        x = e.from;
+    }
+    path.reverse();
+    path
+}
+
+// panics if an edge weight is negative
+pub fn dijkstra(g: &Graph, source: usize) -> ShortestPaths {
+    let mut dist = vec![f64::INFINITY; g.vertices()];
+    let mut edge_to = vec![None; g.vertices()];
+    let mut pq = IndexMinPQ::new(g.vertices());
+
+    dist[source] = 0.0;
+    pq.insert(source, 0.0);
+    while let Some((v, _)) = pq.remove() {
+        for &e in g.adj(v) {
+            assert!(e.weight >= 0.0, "edge {}->{} has a negative weight", e.from, e.to);
+
+            let w = e.to;
+            if dist[v] + e.weight < dist[w] {
+                dist[w] = dist[v] + e.weight;
+                edge_to[w] = Some(e);
+                if pq.contains(w) {
+                    pq.decrease_key(w, dist[w]);
+                } else {
+                    pq.insert(w, dist[w]);
+                }
+            }
+        }
+    }
+
+    ShortestPaths { source, dist, edge_to }
+}
+
+// the length and the edges of a shortest path from source to target, None if target is
+// unreachable. heuristic(v) estimates the distance from v to target, and must not
+// overestimate it for the path to be a shortest one.
+// panics if an edge weight is negative.
+pub fn a_star<H>(g: &Graph, source: usize, target: usize, heuristic: H) -> Option<(f64, Vec<Edge>)>
+where
+    H: Fn(usize) -> f64,
+{
+    let mut dist = vec![f64::INFINITY; g.vertices()];
+    let mut edge_to = vec![None; g.vertices()];
+    // ordered by dist + heuristic
+    let mut pq = IndexMinPQ::new(g.vertices());
+
+    dist[source] = 0.0;
+    pq.insert(source, heuristic(source));
+    while let Some((v, _)) = pq.remove() {
+        if v == target {
+            return Some((dist[v], path(&edge_to, v)));
+        }
+
+        for &e in g.adj(v) {
+            assert!(e.weight >= 0.0, "edge {}->{} has a negative weight", e.from, e.to);
+
+            let w = e.to;
+            if dist[v] + e.weight < dist[w] {
+                dist[w] = dist[v] + e.weight;
+                edge_to[w] = Some(e);
+                let estimate = dist[w] + heuristic(w);
+                // a heuristic that is admissible but not consistent can improve a vertex
+                // that was removed already, which puts it back on the queue
+                if pq.contains(w) {
+                    pq.change_key(w, estimate);
+                } else {
+                    pq.insert(w, estimate);
+                }
+            }
+        }
+    }
+    None
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    // https://algs4.cs.princeton.edu/44sp/tinyEWD.txt
+    fn tiny_ewd() -> Graph {
+        Graph::from_edges(8, &[
+            (4, 5, 0.35), (5, 4, 0.35), (4, 7, 0.37), (5, 7, 0.28), (7, 5, 0.28),
+            (5, 1, 0.32), (0, 4, 0.38), (0, 2, 0.26), (7, 3, 0.39), (1, 3, 0.29),
+            (2, 7, 0.34), (6, 2, 0.40), (3, 6, 0.52), (6, 0, 0.58), (6, 4, 0.93),
+        ])
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let sp = dijkstra(&tiny_ewd(), 0);
+        let expected = [0.0, 1.05, 0.26, 0.99, 0.38, 0.73, 1.51, 0.60];
+        for (v, &d) in expected.iter().enumerate() {
+            assert_close(sp.dist_to(v).unwrap(), d);
+        }
+
+        let path: Vec<_> = sp.path_to(6).unwrap().iter().map(|e| (e.from, e.to)).collect();
+        assert_eq!(path, vec![(0, 2), (2, 7), (7, 3), (3, 6)]);
+        assert_eq!(sp.path_to(0), Some(vec![]));
+        assert_eq!(sp.source(), 0);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let g = Graph::from_edges(3, &[(0, 1, 1.0)]);
+        let sp = dijkstra(&g, 0);
+        assert!(!sp.has_path_to(2));
+        assert_eq!(sp.dist_to(2), None);
+        assert_eq!(sp.path_to(2), None);
+        assert_eq!(a_star(&g, 0, 2, |_| 0.0), None);
+    }
+
+    #[test]
+    fn test_a_star_on_grid() {
+        // a grid with random weights of at least 1, so the manhattan distance never overestimates
+        let mut gen = rand::thread_rng();
+        let n = 30;
+        let id = |x: usize, y: usize| y * n + x;
+        let mut g = Graph::new(n * n);
+        for y in 0..n {
+            for x in 0..n {
+                if x + 1 < n {
+                    g.add_undirected_edge(id(x, y), id(x+1, y), gen.gen_range(1.0, 5.0));
+                }
+                if y + 1 < n {
+                    g.add_undirected_edge(id(x, y), id(x, y+1), gen.gen_range(1.0, 5.0));
+                }
+            }
+        }
+
+        let (tx, ty) = (n-1, n/2);
+        let manhattan = |v: usize| (tx as f64 - (v % n) as f64).abs() + (ty as f64 - (v / n) as f64).abs();
+        let sp = dijkstra(&g, 0);
+
+        let (d, path) = a_star(&g, 0, id(tx, ty), manhattan).unwrap();
+        assert_close(d, sp.dist_to(id(tx, ty)).unwrap());
+        assert_close(path.iter().map(|e| e.weight).sum(), d);
+        assert_eq!(path.first().unwrap().from, 0);
+        assert_eq!(path.last().unwrap().to, id(tx, ty));
+
+        let (d, _) = a_star(&g, 0, id(tx, ty), |_| 0.0).unwrap();
+        assert_close(d, sp.dist_to(id(tx, ty)).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "negative weight")]
+    fn test_negative_weight() {
+        let g = Graph::from_edges(2, &[(0, 1, -1.0)]);
+        dijkstra(&g, 0);
+    }
+}
@@ -0,0 +1,108 @@
+// an edge weighted graph with vertices 0..n, represented with adjacency lists.
+// edges are directed; an undirected edge is stored as a pair of directed edges, one in the
+// adjacency list of each endpoint.
+//
+// inspired by https://algs4.cs.princeton.edu/44sp/EdgeWeightedDigraph.java.html
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Graph {
+    adj: Vec<Vec<Edge>>,
+    edges: usize,
+}
+
+impl Graph {
+    // a graph with vertices 0..n and no edges
+    pub fn new(n: usize) -> Graph {
+        Graph {
+            adj: vec![Vec::new(); n],
+            edges: 0,
+        }
+    }
+
+    // a graph with vertices 0..n and the given directed edges (from, to, weight)
+    pub fn from_edges(n: usize, edges: &[(usize, usize, f64)]) -> Graph {
+        let mut g = Graph::new(n);
+        for &(from, to, weight) in edges {
+            g.add_edge(from, to, weight);
+        }
+        g
+    }
+
+    // a graph with vertices 0..n and the given undirected edges (v, w, weight)
+    pub fn from_undirected_edges(n: usize, edges: &[(usize, usize, f64)]) -> Graph {
+        let mut g = Graph::new(n);
+        for &(v, w, weight) in edges {
+            g.add_undirected_edge(v, w, weight);
+        }
+        g
+    }
+
+    pub fn vertices(&self) -> usize {
+        self.adj.len()
+    }
+
+    // the number of directed edges, an undirected edge counts twice
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    // panics if a vertex is out of range or weight is NaN
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: f64) {
+        assert!(from < self.vertices() && to < self.vertices(),
+            "edge {}->{} out of range {}", from, to, self.vertices());
+        assert!(!weight.is_nan(), "edge {}->{} has a NaN weight", from, to);
+
+        self.adj[from].push(Edge { from, to, weight });
+        self.edges += 1;
+    }
+
+    pub fn add_undirected_edge(&mut self, v: usize, w: usize, weight: f64) {
+        self.add_edge(v, w, weight);
+        self.add_edge(w, v, weight);
+    }
+
+    // the edges leaving v
+    pub fn adj(&self, v: usize) -> &[Edge] {
+        &self.adj[v]
+    }
+
+    // all edges, grouped by the vertex they leave
+    pub fn edges(&self) -> impl Iterator<Item = &Edge> {
+        self.adj.iter().flatten()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph() {
+        let mut g = Graph::from_edges(3, &[(0, 1, 0.5), (1, 2, 1.5)]);
+        g.add_undirected_edge(2, 0, 2.0);
+
+        assert_eq!(g.vertices(), 3);
+        assert_eq!(g.edge_count(), 4);
+        assert_eq!(g.adj(2), &[Edge { from: 2, to: 0, weight: 2.0 }]);
+        assert_eq!(g.adj(0).len(), 2);
+        assert_eq!(g.edges().map(|e| e.weight).sum::<f64>(), 6.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_edge_out_of_range() {
+        let mut g = Graph::new(2);
+        g.add_edge(0, 2, 1.0);
+    }
+}
@@ -0,0 +1,85 @@
+use std::ops::{Index, IndexMut};
+
+use super::Handle;
+
+// node storage for the pointer based heaps that hand out handles.
+// nodes refer to each other by slot index, which keeps the heaps free of unsafe code
+// and of Rc<RefCell<..>> cycles. removed slots are reused, and every reuse bumps the
+// generation of the slot, so a handle to a removed node never refers to its successor.
+pub(crate) struct Arena<N> {
+    slots: Vec<Slot<N>>,
+    free: Vec<usize>,
+}
+
+struct Slot<N> {
+    generation: usize,
+    node: Option<N>,
+}
+
+impl<N> Arena<N> {
+    pub(crate) fn new() -> Arena<N> {
+        Arena {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, node: N) -> Handle {
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.node = Some(node);
+                Handle { index, generation: slot.generation }
+            },
+            None => {
+                self.slots.push(Slot { generation: 0, node: Some(node) });
+                Handle { index: self.slots.len()-1, generation: 0 }
+            },
+        }
+    }
+
+    // panics if the slot is empty
+    pub(crate) fn remove(&mut self, index: usize) -> N {
+        let slot = &mut self.slots[index];
+        let node = slot.node.take().expect("empty slot");
+        slot.generation += 1;
+        self.free.push(index);
+        node
+    }
+
+    // the slot index of a live handle
+    pub(crate) fn index_of(&self, handle: Handle) -> Option<usize> {
+        match self.slots.get(handle.index) {
+            Some(slot) if slot.generation == handle.generation && slot.node.is_some() => Some(handle.index),
+            _ => None,
+        }
+    }
+
+    // move all slots of other behind the slots of self, returning the offset added to
+    // their indices. shift is called on every moved node to relocate its links.
+    pub(crate) fn absorb<F: Fn(&mut N, usize)>(&mut self, other: Arena<N>, shift: F) -> usize {
+        let offset = self.slots.len();
+        self.free.extend(other.free.into_iter().map(|i| i + offset));
+        self.slots.extend(other.slots.into_iter().map(|mut slot| {
+            if let Some(node) = slot.node.as_mut() {
+                shift(node, offset);
+            }
+            slot
+        }));
+        offset
+    }
+}
+
+impl<N> Index<usize> for Arena<N> {
+    type Output = N;
+
+    fn index(&self, index: usize) -> &N {
+        self.slots[index].node.as_ref().expect("empty slot")
+    }
+}
+
+impl<N> IndexMut<usize> for Arena<N> {
+    fn index_mut(&mut self, index: usize) -> &mut N {
+        self.slots[index].node.as_mut().expect("empty slot")
+    }
+}
@@ -1,98 +1,133 @@
+use super::dary_heap::{self, DaryHeap};
+use super::order::{ByKey, Max, Min, Order};
+use super::PriorityQueue;
+
 // a complete binary tree represented in level order using array(vector)
 // parent of node at index k: (k-1)/2
 // children of node at index k: 2*k+1, 2*k+2
 // leaves: [n/2..n], where n == v.len()
-pub struct Heap<T: PartialOrd + Copy> {
-    v: Vec<T>
+//
+// the order decides which element is on top: Max(the default) for a max heap,
+// Min for a min heap, or a comparator / key extractor for anything else.
+//
+// Heap is the binary case of DaryHeap, which implements it, so the two share all code.
+pub type Heap<T, O = Max> = DaryHeap<T, 2, O>;
+
+// a mutable reference to the top element of a heap, see DaryHeap::peek_mut
+pub type PeekMut<'a, T, O> = dary_heap::PeekMut<'a, T, 2, O>;
+
+////////////////////////////////////////////////////////////////////////////////
+// stable heap
+////////////////////////////////////////////////////////////////////////////////
+
+// an element tagged with its insertion sequence number
+struct Stamped<T> {
+    seq: u64,
+    item: T,
 }
 
-impl<T: PartialOrd + Copy> Heap<T> {
-    pub fn new() -> Heap<T> {
-        Heap {
-            v: Vec::new(),
-        }
-    }
+// orders stamped elements by O, and elements that O considers equal by insertion order
+struct Stable<O>(O);
 
-    pub fn from(v: Vec<T>) -> Heap<T> {
-        let mut heap = Heap {
-            v,
-        };
-        heap.fix();
-        heap
+impl<T, O: Order<T>> Order<Stamped<T>> for Stable<O> {
+    fn precedes(&self, a: &Stamped<T>, b: &Stamped<T>) -> bool {
+        let o = &self.0;
+        o.precedes(&a.item, &b.item) || (!o.precedes(&b.item, &a.item) && a.seq < b.seq)
     }
 }
 
-// private methods
-impl<T: PartialOrd + Copy> Heap<T> {
-    // sink implements top-down heapify
-    // sink is used when heap invariant is violated because a node becomes
-    // smaller than one or both of its children.
-    fn sink(&mut self, index: usize) {
-        let mut i = index;
-        // while v[i] is not a leave
-        while i < self.v.len()/2 {
-            let left = i*2+1; // left child
-            let right = i*2+2; // right child
-            let j = if right < self.v.len() && self.v[right] > self.v[left] {
-                right
-            } else {
-                left
-            };
-
-            if self.v[i] >= self.v[j] {
-                break;
-            }
+// a heap that removes elements of equal priority in insertion order(FIFO), at the cost
+// of a sequence number per element. Heap itself makes no guarantee among equal elements.
+pub struct StableHeap<T, O = Max> {
+    heap: Heap<Stamped<T>, Stable<O>>,
+    seq: u64,
+}
 
-            self.v.swap(i, j);
-            i = j;
-        }
+impl<T: PartialOrd> StableHeap<T, Max> {
+    pub fn new() -> StableHeap<T> {
+        StableHeap::with_order(Max)
     }
+}
 
-    // swim implements bottom-up heapify.
-    // swim is used when heap invariant is violated because a node becomes:
-    //     larger than its parent(for max heap);
-    //     smaller than its parent(for min heap).
-    fn swim(&mut self, index: usize) {
-        let mut i = index;
-        while i > 0 && self.v[i] > self.v[(i-1)/2] {
-            self.v.swap(i, (i-1)/2);
-            i = (i-1)/2;
-        }
+impl<T: PartialOrd> StableHeap<T, Min> {
+    pub fn new_min() -> StableHeap<T, Min> {
+        StableHeap::with_order(Min)
     }
+}
 
-    // fix the heap to retain the heap invariant
-    fn fix(&mut self) {
-        for i in (0..self.v.len()/2).rev() {
-            self.sink(i);
-        }
+impl<T, K: PartialOrd, F: Fn(&T) -> K> StableHeap<T, ByKey<F>> {
+    // the element with the largest key is on top, the earliest one among equal keys
+    pub fn new_by_key(key: F) -> StableHeap<T, ByKey<F>> {
+        StableHeap::with_order(ByKey(key))
     }
 }
 
-// public methods
-impl<T: PartialOrd + Copy> Heap<T> {
-    // heap operations normally make a simple modification that violates the heap invariant,
-    // then traveling through and modify the heap as required to retain the heap invariant.
+impl<T, O: Order<T>> StableHeap<T, O> {
+    pub fn with_order(order: O) -> StableHeap<T, O> {
+        StableHeap {
+            heap: Heap::with_order(Stable(order)),
+            seq: 0,
+        }
+    }
 
     pub fn insert(&mut self, target: T) {
-        self.v.push(target);
-        self.swim(self.v.len()-1);
+        self.heap.insert(Stamped { seq: self.seq, item: target });
+        self.seq += 1;
     }
 
     pub fn remove(&mut self) -> Option<T> {
-        if self.v.is_empty() {
-            None
-        } else {
-            let i = self.v.len()-1;
-            self.v.swap(0, i);
+        self.heap.remove().map(|s| s.item)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek().map(|s| &s.item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
 
-            let res = Some(self.v.pop().unwrap());
-            self.sink(0);
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+}
 
-            res
+impl<T, O: Order<T> + Default> Default for StableHeap<T, O> {
+    fn default() -> StableHeap<T, O> {
+        StableHeap::with_order(O::default())
+    }
+}
+
+impl<T, O: Order<T>> Extend<T> for StableHeap<T, O> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.insert(x);
         }
     }
 }
 
+impl<T, O: Order<T>> PriorityQueue<T> for StableHeap<T, O> {
+    fn insert(&mut self, target: T) {
+        StableHeap::insert(self, target);
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        StableHeap::remove(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        StableHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        StableHeap::len(self)
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // tests
 ////////////////////////////////////////////////////////////////////////////////
@@ -101,6 +136,10 @@ impl<T: PartialOrd + Copy> Heap<T> {
 mod tests {
     use super::*;
 
+    use rand::Rng;
+
+    use crate::heap::order::ByComparator;
+
     #[test]
     fn test_remove() {
         let mut h = Heap::from(vec![3,7,6,2,1,5,4,8]);
@@ -146,4 +185,236 @@ mod tests {
             assert_eq!(h.remove().unwrap(), 0);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_min_heap() {
+        let mut h = Heap::from_min(vec![3,7,6,2,1,5,4,8]);
+        h.insert(0);
+
+        for i in 0..=8 {
+            assert_eq!(h.remove().unwrap(), i);
+        }
+        assert_eq!(h.remove(), None);
+    }
+
+    #[test]
+    fn test_float_heap() {
+        let mut h = Heap::new_min();
+        for &x in [2.5, -1.0, 3.75, 0.0].iter() {
+            h.insert(x);
+        }
+
+        assert_eq!(h.remove(), Some(-1.0));
+        assert_eq!(h.remove(), Some(0.0));
+        assert_eq!(h.remove(), Some(2.5));
+        assert_eq!(h.remove(), Some(3.75));
+    }
+
+    #[test]
+    fn test_comparator_heap() {
+        // order by the second component, then by the first in reverse
+        let mut h = Heap::new_by(|a: &(i32, i32), b: &(i32, i32)| a.1.cmp(&b.1).then(b.0.cmp(&a.0)));
+        for &x in [(1, 2), (2, 9), (3, 2), (4, 0), (5, 9)].iter() {
+            h.insert(x);
+        }
+
+        assert_eq!(h.remove(), Some((2, 9)));
+        assert_eq!(h.remove(), Some((5, 9)));
+        assert_eq!(h.remove(), Some((1, 2)));
+        assert_eq!(h.remove(), Some((3, 2)));
+        assert_eq!(h.remove(), Some((4, 0)));
+        assert_eq!(h.remove(), None);
+    }
+
+    #[test]
+    fn test_key_heap() {
+        let mut h = Heap::new_by_key(|x: &i32| (x - 5).abs());
+        for &x in [5, 1, 9, 6, 12].iter() {
+            h.insert(x);
+        }
+
+        assert_eq!(h.remove(), Some(12));
+        let mut tied = vec![h.remove().unwrap(), h.remove().unwrap()];
+        tied.sort();
+        assert_eq!(tied, vec![1, 9]);
+        assert_eq!(h.remove(), Some(6));
+        assert_eq!(h.remove(), Some(5));
+    }
+
+    #[test]
+    fn test_with_order() {
+        let mut h = Heap::from_with_order(vec![4,1,3], Min);
+        h.insert(2);
+
+        assert_eq!(h.remove(), Some(1));
+        assert_eq!(h.remove(), Some(2));
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut h = Heap::new();
+        assert_eq!(h.peek(), None);
+        assert!(h.is_empty());
+
+        h.insert(2);
+        h.insert(5);
+        h.insert(1);
+        assert_eq!(h.peek(), Some(&5));
+        assert_eq!(h.len(), 3);
+        assert!(!h.is_empty());
+
+        h.clear();
+        assert_eq!(h.peek(), None);
+        assert_eq!(h.len(), 0);
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut h = Heap::from(vec![4,8,2,6]);
+        assert!(Heap::<i32>::new().peek_mut().is_none());
+
+        // decreasing the top sinks it
+        *h.peek_mut().unwrap() = 5;
+        assert_eq!(h.peek(), Some(&6));
+
+        // increasing the top keeps it on top
+        {
+            let mut top = h.peek_mut().unwrap();
+            *top += 10;
+        }
+        assert_eq!(h.peek(), Some(&16));
+
+        assert_eq!(PeekMut::pop(h.peek_mut().unwrap()), 16);
+        assert_eq!(h.into_sorted_vec(), vec![2,4,5]);
+    }
+
+    #[test]
+    fn test_iter_and_drain() {
+        let mut h = Heap::from(vec![3,1,2]);
+
+        let mut seen: Vec<_> = h.iter().cloned().collect();
+        seen.sort();
+        assert_eq!(seen, vec![1,2,3]);
+
+        let mut drained: Vec<_> = h.drain().collect();
+        drained.sort();
+        assert_eq!(drained, vec![1,2,3]);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_into_vec() {
+        let mut v = Heap::from(vec![3,1,2]).into_vec();
+        v.sort();
+        assert_eq!(v, vec![1,2,3]);
+
+        assert_eq!(Heap::from(vec![3,7,6,2,1,5,4,8]).into_sorted_vec(), vec![1,2,3,4,5,6,7,8]);
+        assert_eq!(Heap::from_min(vec![3,1,2]).into_sorted_vec(), vec![3,2,1]);
+        assert_eq!(Heap::<i32>::new().into_sorted_vec(), vec![]);
+    }
+
+    #[test]
+    fn test_from_iter_and_extend() {
+        let mut h: Heap<i32> = (0..10).collect();
+        assert_eq!(h.peek(), Some(&9));
+
+        h.extend(vec![20, -1]);
+        assert_eq!(h.len(), 12);
+        assert_eq!(h.remove(), Some(20));
+
+        let h: Heap<i32, Min> = vec![5,3,4].into_iter().collect();
+        assert_eq!(h.peek(), Some(&3));
+    }
+
+    #[test]
+    fn test_append() {
+        let mut a = Heap::from(vec![1,5,3]);
+        let mut b = Heap::from(vec![2,6]);
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_eq!(a.into_sorted_vec(), vec![1,2,3,5,6]);
+
+        // one large and one small heap, in both directions
+        let mut a: Heap<i32> = (0..100).collect();
+        let mut b = Heap::from(vec![1000]);
+        a.append(&mut b);
+        assert_eq!(a.peek(), Some(&1000));
+        b.append(&mut a);
+        assert_eq!(b.len(), 101);
+        assert_eq!(b.into_sorted_vec(), (0..100).chain(1000..1001).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_owned_elements() {
+        let mut h = Heap::new();
+        for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+            h.insert(word.to_string());
+        }
+
+        assert_eq!(h.remove(), Some("the".to_string()));
+        assert_eq!(h.remove(), Some("the".to_string()));
+        assert_eq!(h.peek().map(|s| s.as_str()), Some("quick"));
+        *h.peek_mut().unwrap() = String::from("a");
+        assert_eq!(h.into_sorted_vec(), vec!["a", "brown", "dog", "fox", "jumps", "lazy", "over"]);
+
+        let mut h = Heap::new_by_key(|v: &Vec<i32>| v.len());
+        h.insert(vec![1, 2]);
+        h.insert(vec![]);
+        h.insert(vec![3, 4, 5]);
+        assert_eq!(h.remove(), Some(vec![3, 4, 5]));
+        assert_eq!(h.remove(), Some(vec![1, 2]));
+        assert_eq!(h.remove(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_tasks() {
+        #[derive(Debug, PartialEq)]
+        struct Task {
+            priority: u32,
+            name: String,
+        }
+
+        let mut h = Heap::new_by_key(|t: &Task| t.priority);
+        h.insert(Task { priority: 1, name: "sweep".to_string() });
+        h.insert(Task { priority: 3, name: "deploy".to_string() });
+        h.insert(Task { priority: 2, name: "review".to_string() });
+
+        let names: Vec<_> = std::iter::from_fn(|| h.remove()).map(|t| t.name).collect();
+        assert_eq!(names, vec!["deploy", "review", "sweep"]);
+    }
+
+    #[test]
+    fn test_stable_heap() {
+        let mut h = StableHeap::new_by_key(|job: &(u32, usize)| job.0);
+        let jobs = [(1, 0), (2, 1), (1, 2), (3, 3), (2, 4), (1, 5), (2, 6)];
+        h.extend(jobs.iter().cloned());
+        assert_eq!(h.len(), 7);
+        assert_eq!(h.peek(), Some(&(3, 3)));
+
+        let order: Vec<_> = std::iter::from_fn(|| h.remove()).map(|job| job.1).collect();
+        assert_eq!(order, vec![3, 1, 4, 6, 0, 2, 5]);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_stable_heap_interleaved() {
+        let mut gen = rand::thread_rng();
+        // smallest priority first, the sequence number is not part of the order
+        let mut h = StableHeap::with_order(ByComparator(|a: &(u32, u32), b: &(u32, u32)| b.0.cmp(&a.0)));
+        let mut expected: Vec<(u32, u32)> = Vec::new();
+
+        for i in 0..2000 {
+            if gen.gen_range(0, 3) > 0 {
+                let job = (gen.gen_range(0, 5), i);
+                h.insert(job);
+                expected.push(job);
+            } else {
+                // min_by_key returns the first of equal elements, i.e. the earliest inserted
+                let first = expected.iter().enumerate().min_by_key(|&(_, &(p, _))| p).map(|(j, _)| j);
+                assert_eq!(h.remove(), first.map(|j| expected.remove(j)));
+            }
+        }
+        h.clear();
+        assert_eq!(h.peek(), None);
+    }
+}
@@ -0,0 +1,282 @@
+use std::mem;
+
+use super::order::{Max, Min, Order};
+use super::PriorityQueue;
+
+// a binomial heap is a forest of heap ordered binomial trees, at most one of every rank.
+// a binomial tree of rank k has 2^k nodes and is made of two linked trees of rank k-1,
+// so the trees of a heap with n elements correspond to the 1 bits of n.
+// melding two heaps is like adding two binary numbers: trees of equal rank are linked
+// and carried to the next rank, which takes O(logn).
+//
+// references:
+// https://en.wikipedia.org/wiki/Binomial_heap
+// CLRS, Problem 19-2(3rd edition)
+
+struct Node<T> {
+    item: T,
+    // children[k] is a binomial tree of rank k, so a node with k children has rank k
+    children: Vec<Box<Node<T>>>,
+}
+
+impl<T> Node<T> {
+    fn rank(&self) -> usize {
+        self.children.len()
+    }
+}
+
+// link two trees of equal rank k into a tree of rank k+1
+fn link<T, O: Order<T>>(mut a: Box<Node<T>>, mut b: Box<Node<T>>, order: &O) -> Box<Node<T>> {
+    if order.precedes(&b.item, &a.item) {
+        mem::swap(&mut a, &mut b);
+    }
+    a.children.push(b);
+    a
+}
+
+pub struct BinomialHeap<T, O = Max> {
+    // trees[k] is the tree of rank k, if there is one
+    trees: Vec<Option<Box<Node<T>>>>,
+    len: usize,
+    order: O,
+}
+
+impl<T: PartialOrd> BinomialHeap<T, Max> {
+    pub fn new() -> BinomialHeap<T> {
+        BinomialHeap::with_order(Max)
+    }
+}
+
+impl<T: PartialOrd> BinomialHeap<T, Min> {
+    pub fn new_min() -> BinomialHeap<T, Min> {
+        BinomialHeap::with_order(Min)
+    }
+}
+
+impl<T, O: Order<T>> BinomialHeap<T, O> {
+    pub fn with_order(order: O) -> BinomialHeap<T, O> {
+        BinomialHeap {
+            trees: Vec::new(),
+            len: 0,
+            order,
+        }
+    }
+
+    // amortized O(1): like incrementing a binary counter
+    pub fn insert(&mut self, target: T) {
+        let node = Box::new(Node {
+            item: target,
+            children: Vec::new(),
+        });
+        self.add_tree(node);
+        self.len += 1;
+    }
+
+    pub fn remove(&mut self) -> Option<T> {
+        let k = self.top()?;
+        let node = *self.trees[k].take().unwrap();
+        while let Some(None) = self.trees.last() {
+            self.trees.pop();
+        }
+
+        // the children of a rank k tree form a binomial heap with trees of rank 0..k
+        let children = node.children.into_iter().map(Some).collect();
+        self.merge_trees(children);
+        self.len -= 1;
+        Some(node.item)
+    }
+
+    // O(logn), the top element is one of the roots
+    pub fn peek(&self) -> Option<&T> {
+        self.top().map(|k| &self.trees[k].as_ref().unwrap().item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // move all elements of other into self in O(logn)
+    pub fn meld(&mut self, mut other: BinomialHeap<T, O>) {
+        let trees = mem::take(&mut other.trees);
+        self.merge_trees(trees);
+        self.len += other.len;
+    }
+
+    // rank of the tree whose root is on top
+    fn top(&self) -> Option<usize> {
+        let mut top: Option<usize> = None;
+        for (k, tree) in self.trees.iter().enumerate() {
+            if let Some(node) = tree {
+                match top {
+                    Some(t) if !self.order.precedes(&node.item, &self.trees[t].as_ref().unwrap().item) => (),
+                    _ => top = Some(k),
+                }
+            }
+        }
+        top
+    }
+
+    // add a single tree, carrying to higher ranks while there is a tree of the same rank
+    fn add_tree(&mut self, mut tree: Box<Node<T>>) {
+        let mut k = tree.rank();
+        loop {
+            if k == self.trees.len() {
+                self.trees.push(None);
+            }
+            match self.trees[k].take() {
+                Some(other) => {
+                    tree = link(tree, other, &self.order);
+                    k += 1;
+                },
+                None => {
+                    self.trees[k] = Some(tree);
+                    return;
+                },
+            }
+        }
+    }
+
+    // binary addition of two forests indexed by rank
+    fn merge_trees(&mut self, other: Vec<Option<Box<Node<T>>>>) {
+        let mut carry: Option<Box<Node<T>>> = None;
+        let mut other = other.into_iter();
+        let mut k = 0;
+
+        loop {
+            let b = other.next();
+            if b.is_none() && carry.is_none() {
+                break;
+            }
+            if k == self.trees.len() {
+                self.trees.push(None);
+            }
+
+            let mut present: Vec<Box<Node<T>>> = Vec::with_capacity(3);
+            present.extend(self.trees[k].take());
+            present.extend(b.and_then(|b| b));
+            present.extend(carry.take());
+
+            // 1 tree stays, 2 trees are carried, 3 trees: one stays and two are carried
+            if present.len() % 2 == 1 {
+                self.trees[k] = present.pop();
+            }
+            if present.len() == 2 {
+                let b = present.pop().unwrap();
+                let a = present.pop().unwrap();
+                carry = Some(link(a, b, &self.order));
+            }
+            k += 1;
+        }
+
+        while let Some(None) = self.trees.last() {
+            self.trees.pop();
+        }
+    }
+}
+
+impl<T, O: Order<T> + Default> Default for BinomialHeap<T, O> {
+    fn default() -> BinomialHeap<T, O> {
+        BinomialHeap::with_order(O::default())
+    }
+}
+
+impl<T, O: Order<T>> PriorityQueue<T> for BinomialHeap<T, O> {
+    fn insert(&mut self, target: T) {
+        BinomialHeap::insert(self, target);
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        BinomialHeap::remove(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        BinomialHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        BinomialHeap::len(self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    // check heap order and shape, returns the number of nodes
+    fn assert_binomial<T, O: Order<T>>(node: &Node<T>, order: &O) -> usize {
+        let mut size = 1;
+        for (k, child) in node.children.iter().enumerate() {
+            assert_eq!(child.rank(), k);
+            assert!(!order.precedes(&child.item, &node.item));
+            size += assert_binomial(child, order);
+        }
+        assert_eq!(size, 1 << node.rank());
+        size
+    }
+
+    fn assert_heap<T, O: Order<T>>(h: &BinomialHeap<T, O>) {
+        let mut size = 0;
+        for (k, tree) in h.trees.iter().enumerate() {
+            if let Some(node) = tree {
+                assert_eq!(node.rank(), k);
+                size += assert_binomial(node, &h.order);
+            }
+        }
+        assert_eq!(size, h.len());
+        assert!(!matches!(h.trees.last(), Some(None)));
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut h = BinomialHeap::new();
+        for &x in [3,7,6,2,1,5,4,8].iter() {
+            h.insert(x);
+            assert_heap(&h);
+        }
+        // 8 elements make a single tree of rank 3
+        assert_eq!(h.trees.len(), 4);
+        assert_eq!(h.peek(), Some(&8));
+
+        for i in (1..=8).rev() {
+            assert_eq!(h.remove(), Some(i));
+            assert_heap(&h);
+        }
+        assert_eq!(h.remove(), None);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut gen = rand::thread_rng();
+        for _ in 0..20 {
+            let mut a = BinomialHeap::new_min();
+            let mut b = BinomialHeap::new_min();
+            let mut expected = Vec::new();
+            for _ in 0..gen.gen_range(0, 200) {
+                let x = gen.gen_range(0, 100);
+                expected.push(x);
+                if gen.gen() {
+                    a.insert(x);
+                } else {
+                    b.insert(x);
+                }
+            }
+
+            a.meld(b);
+            assert_heap(&a);
+            expected.sort();
+            let removed: Vec<_> = std::iter::from_fn(|| a.remove()).collect();
+            assert_eq!(removed, expected);
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::binary_heap::Heap;
+use super::order::{Max, Min, Order};
+
+// a priority queue shared between threads, e.g. a job queue with several producers and
+// workers. remove blocks until an element is available or the queue is closed.
+//
+// the elements are spread over several heaps(shards), each guarded by its own lock, so
+// threads inserting at the same time rarely wait for each other: an insert takes the first
+// free shard, starting from a different one each time. a remove scans the shard tops and
+// takes the one that comes first, holding the lock of the best shard seen so far and at most
+// one more. locks are always taken in the order of the shards, so there is no deadlock.
+//
+// the shards are locked one after another rather than all at once, so an element inserted
+// into a shard that a remove has already scanned can be missed by that remove: the removed
+// element is then not the true top. without concurrent inserts every remove returns the
+// true top at the time it runs, and no element is ever lost or removed twice.
+//
+// a blocked remove waits on a condition variable, which an insert only signals when some
+// thread is waiting, so the common path takes a single shard lock.
+pub struct ConcurrentHeap<T, O = Max> {
+    shards: Vec<Mutex<Heap<T, O>>>,
+    order: O,
+    // the shard the next insert tries first
+    next: AtomicUsize,
+    // only changed while all shards are locked, so an insert checks it under its shard lock
+    closed: AtomicBool,
+    // the number of threads in a blocking remove, changed while holding waiting
+    waiters: AtomicUsize,
+    waiting: Mutex<()>,
+    // signalled when an element is inserted while a thread is waiting, or on close
+    available: Condvar,
+}
+
+impl<T: PartialOrd> ConcurrentHeap<T, Max> {
+    pub fn new() -> ConcurrentHeap<T> {
+        ConcurrentHeap::with_order(Max)
+    }
+}
+
+impl<T: PartialOrd> ConcurrentHeap<T, Min> {
+    pub fn new_min() -> ConcurrentHeap<T, Min> {
+        ConcurrentHeap::with_order(Min)
+    }
+}
+
+impl<T, O: Order<T> + Clone> ConcurrentHeap<T, O> {
+    // a shard per available cpu
+    pub fn with_order(order: O) -> ConcurrentHeap<T, O> {
+        let shards = thread::available_parallelism().map_or(4, |n| n.get());
+        ConcurrentHeap::with_shards(shards, order)
+    }
+
+    // panics if shards is 0
+    pub fn with_shards(shards: usize, order: O) -> ConcurrentHeap<T, O> {
+        assert!(shards > 0, "a concurrent heap needs at least one shard");
+
+        ConcurrentHeap {
+            shards: (0..shards).map(|_| Mutex::new(Heap::with_order(order.clone()))).collect(),
+            order,
+            next: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+            waiters: AtomicUsize::new(0),
+            waiting: Mutex::new(()),
+            available: Condvar::new(),
+        }
+    }
+
+    // returns target back if the queue is closed
+    pub fn insert(&self, target: T) -> Result<(), T> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % self.shards.len();
+        let mut shard = (0..self.shards.len())
+            .find_map(|i| self.shards[(start + i) % self.shards.len()].try_lock().ok())
+            .unwrap_or_else(|| lock(&self.shards[start]));
+
+        if self.closed.load(Ordering::Relaxed) {
+            return Err(target);
+        }
+        shard.insert(target);
+        drop(shard);
+
+        // a waiter counts itself before scanning the shards, and the scan locks the shard
+        // just released, so either the waiter finds the element or it is counted here
+        if self.waiters.load(Ordering::SeqCst) > 0 {
+            let _waiting = lock(&self.waiting);
+            self.available.notify_one();
+        }
+        Ok(())
+    }
+
+    // remove the top element without waiting
+    pub fn try_remove(&self) -> Option<T> {
+        let mut best: Option<MutexGuard<'_, Heap<T, O>>> = None;
+        for shard in self.shards.iter() {
+            let shard = lock(shard);
+            let better = match (shard.peek(), best.as_ref().and_then(|b| b.peek())) {
+                (Some(top), Some(best_top)) => self.order.precedes(top, best_top),
+                (top, _) => top.is_some(),
+            };
+            if better {
+                best = Some(shard);
+            }
+        }
+        best?.remove()
+    }
+
+    // remove the top element, waiting for one to be inserted if the queue is empty.
+    // returns None once the queue is closed and empty.
+    pub fn remove(&self) -> Option<T> {
+        self.wait_for(None)
+    }
+
+    // like remove, but gives up and returns None after waiting for timeout
+    pub fn remove_timeout(&self, timeout: Duration) -> Option<T> {
+        self.wait_for(Some(Instant::now() + timeout))
+    }
+
+    // stop accepting elements and wake up all waiting threads.
+    // the elements already on the queue can still be removed.
+    pub fn close(&self) {
+        // with all shards locked no insert is half done: it either completed before or
+        // sees closed
+        let shards: Vec<_> = self.shards.iter().map(lock).collect();
+        self.closed.store(true, Ordering::SeqCst);
+        drop(shards);
+
+        let _waiting = lock(&self.waiting);
+        self.available.notify_all();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    // the number of elements at the time of the call, which other threads may change
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| lock(shard).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| lock(shard).is_empty())
+    }
+
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn wait_for(&self, deadline: Option<Instant>) -> Option<T> {
+        if let Some(top) = self.try_remove() {
+            return Some(top);
+        }
+
+        let mut waiting = lock(&self.waiting);
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        let res = loop {
+            // read closed before scanning: once closed, every accepted element is on a shard
+            let closed = self.is_closed();
+            if let Some(top) = self.try_remove() {
+                break Some(top);
+            }
+            if closed {
+                break None;
+            }
+
+            // a wakeup may be spurious or lose the race for the element, so wait again
+            // for the rest of the timeout
+            waiting = match deadline {
+                None => self.available.wait(waiting).expect("heap lock poisoned"),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break None;
+                    }
+                    self.available.wait_timeout(waiting, deadline - now).expect("heap lock poisoned").0
+                },
+            };
+        };
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+        res
+    }
+}
+
+// a thread panics while holding a lock only if the order panics, in which case the heap
+// may be left in an invalid state
+fn lock<S>(mutex: &Mutex<S>) -> MutexGuard<'_, S> {
+    mutex.lock().expect("heap lock poisoned")
+}
+
+impl<T, O: Order<T> + Clone + Default> Default for ConcurrentHeap<T, O> {
+    fn default() -> ConcurrentHeap<T, O> {
+        ConcurrentHeap::with_order(O::default())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_single_thread() {
+        let h = ConcurrentHeap::new();
+        for &x in [3,7,6,2,1,5,4,8].iter() {
+            h.insert(x).unwrap();
+        }
+        assert_eq!(h.len(), 8);
+
+        for i in (1..=8).rev() {
+            assert_eq!(h.remove(), Some(i));
+        }
+        assert_eq!(h.try_remove(), None);
+        assert_eq!(h.remove_timeout(Duration::from_millis(10)), None);
+
+        h.insert(1).unwrap();
+        h.close();
+        assert!(h.is_closed());
+        assert_eq!(h.insert(2), Err(2));
+        assert_eq!(h.remove(), Some(1));
+        assert_eq!(h.remove(), None);
+    }
+
+    #[test]
+    fn test_producers_and_consumers() {
+        let producers = 4;
+        let consumers = 4;
+        let per_producer = 10_000;
+        let h = Arc::new(ConcurrentHeap::new_min());
+
+        let consumer_threads: Vec<_> = (0..consumers).map(|_| {
+            let h = Arc::clone(&h);
+            thread::spawn(move || {
+                let mut removed = Vec::new();
+                while let Some(x) = h.remove() {
+                    removed.push(x);
+                }
+                removed
+            })
+        }).collect();
+
+        let producer_threads: Vec<_> = (0..producers).map(|p| {
+            let h = Arc::clone(&h);
+            thread::spawn(move || {
+                for i in 0..per_producer {
+                    h.insert(i * producers + p).unwrap();
+                }
+            })
+        }).collect();
+
+        for t in producer_threads {
+            t.join().unwrap();
+        }
+        h.close();
+
+        let mut all: Vec<_> = consumer_threads.into_iter().flat_map(|t| t.join().unwrap()).collect();
+        all.sort();
+        assert_eq!(all, (0..producers * per_producer).collect::<Vec<_>>());
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_removes_are_ordered() {
+        // with all elements inserted up front, every remove returns the current top,
+        // so each thread sees a decreasing sequence
+        let h = Arc::new(ConcurrentHeap::new());
+        for i in 0..20_000 {
+            h.insert(i).unwrap();
+        }
+
+        let threads: Vec<_> = (0..4).map(|_| {
+            let h = Arc::clone(&h);
+            thread::spawn(move || {
+                let mut removed = Vec::new();
+                while let Some(x) = h.try_remove() {
+                    removed.push(x);
+                }
+                removed
+            })
+        }).collect();
+
+        let mut total = 0;
+        for t in threads {
+            let removed = t.join().unwrap();
+            assert!(removed.windows(2).all(|w| w[0] > w[1]));
+            total += removed.len();
+        }
+        assert_eq!(total, 20_000);
+    }
+
+    #[test]
+    fn test_blocking_remove_wakes_up() {
+        // a min heap, so the result does not depend on whether the waiter blocks in time
+        let h = Arc::new(ConcurrentHeap::new_min());
+        let waiter = {
+            let h = Arc::clone(&h);
+            thread::spawn(move || (h.remove(), h.remove_timeout(Duration::from_secs(10))))
+        };
+
+        thread::sleep(Duration::from_millis(20));
+        h.insert(1).unwrap();
+        thread::sleep(Duration::from_millis(20));
+        h.insert(2).unwrap();
+        assert_eq!(waiter.join().unwrap(), (Some(1), Some(2)));
+
+        // close wakes up a blocked remove
+        let waiter = {
+            let h = Arc::clone(&h);
+            thread::spawn(move || h.remove())
+        };
+        thread::sleep(Duration::from_millis(20));
+        h.close();
+        assert_eq!(waiter.join().unwrap(), None);
+    }
+
+    #[test]
+    fn test_stress() {
+        // threads insert and remove at the same time with every kind of remove, and every
+        // inserted element must come out exactly once
+        let threads = 8;
+        let per_thread = 20_000;
+        let h = Arc::new(ConcurrentHeap::with_shards(3, Max));
+
+        let workers: Vec<_> = (0..threads).map(|t| {
+            let h = Arc::clone(&h);
+            thread::spawn(move || {
+                let mut removed = Vec::new();
+                for i in 0..per_thread {
+                    h.insert(i * threads + t).unwrap();
+                    let x = match i % 3 {
+                        0 => h.try_remove(),
+                        1 => h.remove_timeout(Duration::from_millis(1)),
+                        _ => None,
+                    };
+                    removed.extend(x);
+                }
+                removed
+            })
+        }).collect();
+
+        // drain what the workers left behind, while they are still inserting
+        let drainer = {
+            let h = Arc::clone(&h);
+            thread::spawn(move || {
+                let mut removed = Vec::new();
+                while let Some(x) = h.remove() {
+                    removed.push(x);
+                }
+                removed
+            })
+        };
+
+        let mut all: Vec<_> = workers.into_iter().flat_map(|t| t.join().unwrap()).collect();
+        h.close();
+        all.extend(drainer.join().unwrap());
+
+        all.sort();
+        assert_eq!(all, (0..threads * per_thread).collect::<Vec<_>>());
+        assert!(h.is_empty());
+        assert_eq!(h.shards(), 3);
+    }
+}
@@ -0,0 +1,490 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Write};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+use std::slice;
+use std::vec;
+
+use super::order::{ByComparator, ByKey, Max, Min, Order};
+use super::sift::Sift;
+use super::PriorityQueue;
+
+// a complete d-ary tree represented in level order using array(vector)
+// parent of node at index k: (k-1)/d
+// children of node at index k: d*k+1, ..., d*k+d
+//
+// compared to the binary Heap, a d-ary heap is shallower(log_d(n) levels), so swim
+// (insert, and decreasing a key in a min heap) is cheaper, while sink (remove) has to
+// compare d children on every level. the children of a node are adjacent in memory,
+// which makes 4-ary and 8-ary heaps more cache friendly than binary ones.
+//
+// the order decides which element is on top: Max(the default) for a max heap,
+// Min for a min heap, or a comparator / key extractor for anything else.
+// binary_heap::Heap is the case D = 2.
+pub struct DaryHeap<T, const D: usize, O = Max> {
+    v: Vec<T>,
+    order: O,
+}
+
+impl<T: PartialOrd, const D: usize> DaryHeap<T, D, Max> {
+    pub fn new() -> DaryHeap<T, D> {
+        DaryHeap::with_order(Max)
+    }
+
+    pub fn from(v: Vec<T>) -> DaryHeap<T, D> {
+        DaryHeap::from_with_order(v, Max)
+    }
+}
+
+impl<T: PartialOrd, const D: usize> DaryHeap<T, D, Min> {
+    pub fn new_min() -> DaryHeap<T, D, Min> {
+        DaryHeap::with_order(Min)
+    }
+
+    pub fn from_min(v: Vec<T>) -> DaryHeap<T, D, Min> {
+        DaryHeap::from_with_order(v, Min)
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering, const D: usize> DaryHeap<T, D, ByComparator<F>> {
+    // the greatest element according to compare is on top
+    pub fn new_by(compare: F) -> DaryHeap<T, D, ByComparator<F>> {
+        DaryHeap::with_order(ByComparator(compare))
+    }
+}
+
+impl<T, K: PartialOrd, F: Fn(&T) -> K, const D: usize> DaryHeap<T, D, ByKey<F>> {
+    // the element with the largest key is on top
+    pub fn new_by_key(key: F) -> DaryHeap<T, D, ByKey<F>> {
+        DaryHeap::with_order(ByKey(key))
+    }
+}
+
+impl<T, O: Order<T>, const D: usize> DaryHeap<T, D, O> {
+    pub fn with_order(order: O) -> DaryHeap<T, D, O> {
+        assert!(D >= 2, "a d-ary heap needs at least 2 children per node");
+        DaryHeap {
+            v: Vec::new(),
+            order,
+        }
+    }
+
+    pub fn from_with_order(v: Vec<T>, order: O) -> DaryHeap<T, D, O> {
+        let mut heap = DaryHeap::with_order(order);
+        heap.v = v;
+        heap.fix();
+        heap
+    }
+}
+
+// sink and swim are shared with the other array based heaps
+impl<T, O: Order<T>, const D: usize> Sift for DaryHeap<T, D, O> {
+    const ARITY: usize = D;
+
+    fn heap_len(&self) -> usize {
+        self.v.len()
+    }
+
+    fn precedes(&self, i: usize, j: usize) -> bool {
+        self.order.precedes(&self.v[i], &self.v[j])
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.v.swap(i, j);
+    }
+}
+
+// public methods
+impl<T, O: Order<T>, const D: usize> DaryHeap<T, D, O> {
+    // heap operations normally make a simple modification that violates the heap invariant,
+    // then traveling through and modify the heap as required to retain the heap invariant.
+
+    pub fn insert(&mut self, target: T) {
+        self.v.push(target);
+        self.swim(self.v.len()-1);
+    }
+
+    pub fn remove(&mut self) -> Option<T> {
+        if self.v.is_empty() {
+            None
+        } else {
+            let i = self.v.len()-1;
+            self.v.swap(0, i);
+
+            let res = Some(self.v.pop().unwrap());
+            self.sink(0);
+
+            res
+        }
+    }
+
+    // the top element, without removing it
+    pub fn peek(&self) -> Option<&T> {
+        self.v.first()
+    }
+
+    // a mutable reference to the top element, the heap invariant is restored
+    // when the returned PeekMut goes out of scope.
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, D, O>> {
+        if self.v.is_empty() {
+            None
+        } else {
+            Some(PeekMut {
+                heap: self,
+                sift: true,
+            })
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.v.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.v.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.v.clear();
+    }
+
+    // iterate over all elements in arbitrary order
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.v.iter()
+    }
+
+    // remove all elements in arbitrary order
+    pub fn drain(&mut self) -> vec::Drain<'_, T> {
+        self.v.drain(..)
+    }
+
+    pub fn order(&self) -> &O {
+        &self.order
+    }
+
+    // the underlying vector, in arbitrary order
+    pub fn into_vec(self) -> Vec<T> {
+        self.v
+    }
+
+    // all elements sorted so that the top element comes last,
+    // i.e. in ascending order for a max heap and in descending order for a min heap.
+    // this is the second phase of heap sort.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut end = self.v.len();
+        while end > 1 {
+            end -= 1;
+            self.v.swap(0, end);
+            self.sink_to(0, end);
+        }
+
+        self.v
+    }
+
+    // move all elements of other into self, leaving other empty
+    pub fn append(&mut self, other: &mut Self) {
+        if other.v.len() > self.v.len() {
+            std::mem::swap(&mut self.v, &mut other.v);
+        }
+
+        // inserting one by one costs O(mlogn), rebuilding costs O(n+m)
+        if other.v.len() * 2 < self.v.len() {
+            for x in other.v.drain(..) {
+                self.insert(x);
+            }
+        } else {
+            self.v.append(&mut other.v);
+            self.fix();
+        }
+    }
+}
+
+impl<T, O: Order<T> + Default, const D: usize> Default for DaryHeap<T, D, O> {
+    fn default() -> DaryHeap<T, D, O> {
+        DaryHeap::with_order(O::default())
+    }
+}
+
+impl<T, O: Order<T> + Default, const D: usize> FromIterator<T> for DaryHeap<T, D, O> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> DaryHeap<T, D, O> {
+        DaryHeap::from_with_order(iter.into_iter().collect(), O::default())
+    }
+}
+
+impl<T, O: Order<T>, const D: usize> Extend<T> for DaryHeap<T, D, O> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.insert(x);
+        }
+    }
+}
+
+impl<T, O: Order<T>, const D: usize> PriorityQueue<T> for DaryHeap<T, D, O> {
+    fn insert(&mut self, target: T) {
+        DaryHeap::insert(self, target);
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        DaryHeap::remove(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        DaryHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        DaryHeap::len(self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// debugging
+////////////////////////////////////////////////////////////////////////////////
+
+impl<T, O: Order<T>, const D: usize> DaryHeap<T, D, O> {
+    // check the heap invariant, returning the first (parent, child) index pair in level
+    // order where the child should be placed above its parent
+    pub fn validate(&self) -> Result<(), (usize, usize)> {
+        for child in 1..self.v.len() {
+            let parent = (child-1)/D;
+            if self.precedes(child, parent) {
+                return Err((parent, child));
+            }
+        }
+        Ok(())
+    }
+
+    // the tree drawn with one element per line, children indented below their parent:
+    //     8
+    //     ├── 7
+    //     │   ├── 4
+    //     │   └── 2
+    //     └── 6
+    pub fn to_ascii(&self) -> String
+    where
+        T: Debug,
+    {
+        let mut out = String::new();
+        if self.v.is_empty() {
+            return out;
+        }
+
+        // (index, prefix of the line, prefix of the lines below it)
+        let mut stack = vec![(0, String::new(), String::new())];
+        while let Some((i, line, below)) = stack.pop() {
+            writeln!(out, "{}{:?}", line, self.v[i]).unwrap();
+
+            let children: Vec<usize> = (D*i+1..=D*i+D).filter(|&c| c < self.v.len()).collect();
+            // pushed in reverse, so that the leftmost child is drawn first
+            for (k, &c) in children.iter().enumerate().rev() {
+                if k + 1 == children.len() {
+                    stack.push((c, format!("{}└── ", below), format!("{}    ", below)));
+                } else {
+                    stack.push((c, format!("{}├── ", below), format!("{}│   ", below)));
+                }
+            }
+        }
+        out
+    }
+
+    // the tree in Graphviz DOT format, e.g. to render with `dot -Tsvg`.
+    // nodes are labeled with their elements, edges violating the heap invariant are red.
+    pub fn to_dot(&self) -> String
+    where
+        T: Debug,
+    {
+        let mut out = String::from("digraph heap {\n");
+        for (i, x) in self.v.iter().enumerate() {
+            let label = format!("{:?}", x).replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(out, "    n{} [label=\"{}\"];", i, label).unwrap();
+        }
+        for child in 1..self.v.len() {
+            let parent = (child-1)/D;
+            let color = if self.precedes(child, parent) { " [color=red]" } else { "" };
+            writeln!(out, "    n{} -> n{}{};", parent, child, color).unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// peek mut
+////////////////////////////////////////////////////////////////////////////////
+
+// a mutable reference to the top element of a heap.
+// the top element may sink after being modified, so PeekMut restores the heap invariant
+// when dropped.
+pub struct PeekMut<'a, T, const D: usize, O: Order<T>> {
+    heap: &'a mut DaryHeap<T, D, O>,
+    sift: bool,
+}
+
+impl<'a, T, O: Order<T>, const D: usize> PeekMut<'a, T, D, O> {
+    // remove the peeked element from the heap
+    pub fn pop(mut this: PeekMut<'a, T, D, O>) -> T {
+        // remove() restores the heap invariant already
+        this.sift = false;
+        this.heap.remove().unwrap()
+    }
+}
+
+impl<'a, T, O: Order<T>, const D: usize> Deref for PeekMut<'a, T, D, O> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.v[0]
+    }
+}
+
+impl<'a, T, O: Order<T>, const D: usize> DerefMut for PeekMut<'a, T, D, O> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.heap.v[0]
+    }
+}
+
+impl<'a, T, O: Order<T>, const D: usize> Drop for PeekMut<'a, T, D, O> {
+    fn drop(&mut self) {
+        if self.sift {
+            self.heap.sink(0);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    use crate::heap::binary_heap::Heap;
+
+    fn check_sorted_removal<const D: usize>(v: Vec<u32>) {
+        let mut expected = v.clone();
+        expected.sort();
+
+        let mut h: DaryHeap<u32, D> = DaryHeap::new();
+        for &x in v.iter() {
+            h.insert(x);
+        }
+        for &x in expected.iter().rev() {
+            assert_eq!(h.remove(), Some(x));
+        }
+        assert_eq!(h.remove(), None);
+
+        assert_eq!(DaryHeap::<u32, D>::from(v).into_sorted_vec(), expected);
+    }
+
+    #[test]
+    fn test_arities() {
+        let mut gen = rand::thread_rng();
+        for &len in [0, 1, 2, 3, 5, 17, 100, 1000].iter() {
+            let v: Vec<u32> = (0..len).map(|_| gen.gen_range(0, 100)).collect();
+            check_sorted_removal::<2>(v.clone());
+            check_sorted_removal::<3>(v.clone());
+            check_sorted_removal::<4>(v.clone());
+            check_sorted_removal::<8>(v.clone());
+            check_sorted_removal::<16>(v);
+        }
+    }
+
+    #[test]
+    fn test_min_and_custom_orders() {
+        let mut h: DaryHeap<i32, 4, Min> = DaryHeap::from_min(vec![3,7,6,2,1,5,4,8]);
+        h.insert(0);
+        for i in 0..=8 {
+            assert_eq!(h.remove(), Some(i));
+        }
+
+        let mut h: DaryHeap<_, 4, _> = DaryHeap::new_by_key(|s: &String| s.len());
+        h.extend(vec!["a".to_string(), "abc".to_string(), "ab".to_string()]);
+        assert_eq!(h.peek().map(|s| s.as_str()), Some("abc"));
+
+        let h: DaryHeap<_, 3, _> = DaryHeap::new_by(|a: &i32, b: &i32| b.cmp(a));
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut h: DaryHeap<i32, 4> = (0..20).collect();
+        *h.peek_mut().unwrap() = -1;
+        assert_eq!(h.peek(), Some(&18));
+        assert_eq!(PeekMut::pop(h.peek_mut().unwrap()), 18);
+        assert_eq!(h.len(), 19);
+    }
+
+    #[test]
+    fn test_append_and_drain() {
+        let mut a: DaryHeap<i32, 4> = (0..10).collect();
+        let mut b: DaryHeap<i32, 4> = (10..15).collect();
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_eq!(a.len(), 15);
+        assert_eq!(a.iter().count(), 15);
+
+        let mut drained: Vec<_> = a.drain().collect();
+        drained.sort();
+        assert_eq!(drained, (0..15).collect::<Vec<_>>());
+        assert!(a.is_empty());
+
+        a.insert(3);
+        a.clear();
+        assert_eq!(a.into_vec(), vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 2 children")]
+    fn test_unary_heap() {
+        let _: DaryHeap<i32, 1> = DaryHeap::new();
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut h = Heap::from(vec![3,7,6,2,1,5,4,8]);
+        assert_eq!(h.validate(), Ok(()));
+        assert_eq!(Heap::<i32>::new().validate(), Ok(()));
+
+        // break the invariant below the root
+        h.v[4] = 100;
+        assert_eq!(h.validate(), Err((1, 4)));
+
+        let mut h = Heap::from_min(vec![1, 2, 3, 4, 5]);
+        h.v.swap(0, 4);
+        assert_eq!(h.validate(), Err((0, 1)));
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        let h = Heap::from(vec![2,4,7,6,8]);
+        let expected = "\
+8
+├── 6
+│   ├── 2
+│   └── 4
+└── 7
+";
+        assert_eq!(h.to_ascii(), expected);
+        assert_eq!(Heap::<i32>::new().to_ascii(), "");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut h = Heap::from(vec!["b", "a", "c"]);
+        assert_eq!(h.to_dot(), "\
+digraph heap {
+    n0 [label=\"\\\"c\\\"\"];
+    n1 [label=\"\\\"a\\\"\"];
+    n2 [label=\"\\\"b\\\"\"];
+    n0 -> n1;
+    n0 -> n2;
+}
+");
+
+        h.v.swap(0, 2);
+        assert!(h.to_dot().contains("n0 -> n2 [color=red];"));
+    }
+}
@@ -0,0 +1,477 @@
+use std::mem;
+
+use super::arena::Arena;
+use super::order::{Max, Min, Order};
+use super::{Handle, PriorityQueue};
+
+// a fibonacci heap is a collection of heap ordered trees whose roots form a circular
+// doubly linked list. insert and meld only add trees to the root list, and the trees are
+// consolidated(linked by degree, like a binomial heap) only when the top is removed.
+// decrease_key cuts the node from its parent; a node that loses a second child is cut as
+// well(cascading cut), which keeps the degree of every node O(logn).
+//
+// insert, peek, meld and decrease_key take amortized O(1), remove and delete O(logn),
+// which makes Dijkstra's algorithm O(m + nlogn).
+//
+// references:
+// https://en.wikipedia.org/wiki/Fibonacci_heap
+// CLRS, Chapter 19(3rd edition)
+
+struct Node<T> {
+    item: T,
+    parent: Option<usize>,
+    // any of the children
+    child: Option<usize>,
+    // siblings, in a circular doubly linked list
+    left: usize,
+    right: usize,
+    degree: usize,
+    // whether the node has lost a child since it became a child itself
+    marked: bool,
+}
+
+pub struct FibonacciHeap<T, O = Max> {
+    nodes: Arena<Node<T>>,
+    // the top root, the root list is reached through it
+    top: Option<usize>,
+    len: usize,
+    order: O,
+}
+
+impl<T: PartialOrd> FibonacciHeap<T, Max> {
+    pub fn new() -> FibonacciHeap<T> {
+        FibonacciHeap::with_order(Max)
+    }
+}
+
+impl<T: PartialOrd> FibonacciHeap<T, Min> {
+    pub fn new_min() -> FibonacciHeap<T, Min> {
+        FibonacciHeap::with_order(Min)
+    }
+}
+
+impl<T, O: Order<T>> FibonacciHeap<T, O> {
+    pub fn with_order(order: O) -> FibonacciHeap<T, O> {
+        FibonacciHeap {
+            nodes: Arena::new(),
+            top: None,
+            len: 0,
+            order,
+        }
+    }
+
+    // O(1), the handle can be used to decrease the key of target or delete it later
+    pub fn insert(&mut self, target: T) -> Handle {
+        let handle = self.nodes.insert(Node {
+            item: target,
+            parent: None,
+            child: None,
+            left: 0,
+            right: 0,
+            degree: 0,
+            marked: false,
+        });
+
+        let x = handle.index;
+        self.nodes[x].left = x;
+        self.nodes[x].right = x;
+        self.add_root(x);
+        self.len += 1;
+        handle
+    }
+
+    pub fn remove(&mut self) -> Option<T> {
+        let z = self.top?;
+
+        // the children of z become roots
+        if let Some(c) = self.nodes[z].child.take() {
+            let mut x = c;
+            loop {
+                self.nodes[x].parent = None;
+                self.nodes[x].marked = false;
+                x = self.nodes[x].right;
+                if x == c {
+                    break;
+                }
+            }
+            self.splice(z, c);
+        }
+
+        let next = self.nodes[z].right;
+        self.unlink(z);
+        if next == z {
+            self.top = None;
+        } else {
+            self.consolidate(next);
+        }
+
+        self.len -= 1;
+        Some(self.nodes.remove(z).item)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.top.map(|x| &self.nodes[x].item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.nodes.index_of(handle).is_some()
+    }
+
+    // the element handle refers to, or None if it has been removed
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.nodes.index_of(handle).map(|x| &self.nodes[x].item)
+    }
+
+    // replace the element handle refers to with one that is ordered at least as close
+    // to the top, returning the old element. amortized O(1).
+    // panics if handle is not on the heap, or if target is ordered after the old element.
+    pub fn decrease_key(&mut self, handle: Handle, target: T) -> T {
+        let x = self.position(handle);
+        if self.order.precedes(&self.nodes[x].item, &target) {
+            panic!("decrease_key() with a key ordered after the current key");
+        }
+        let old = mem::replace(&mut self.nodes[x].item, target);
+
+        if let Some(p) = self.nodes[x].parent {
+            if self.precedes(x, p) {
+                self.cut(x, p);
+                self.cascading_cut(p);
+            }
+        }
+        if self.precedes(x, self.top.unwrap()) {
+            self.top = Some(x);
+        }
+        old
+    }
+
+    // remove the element handle refers to, or return None if it has been removed already
+    pub fn delete(&mut self, handle: Handle) -> Option<T> {
+        let x = self.nodes.index_of(handle)?;
+
+        // move x to the root list and treat it as the top, no matter its key
+        if let Some(p) = self.nodes[x].parent {
+            self.cut(x, p);
+            self.cascading_cut(p);
+        }
+        self.top = Some(x);
+        self.remove()
+    }
+
+    // move all elements of other into self in O(1).
+    // handles returned by other are not valid for self, the returned function maps them
+    // to handles of the same elements in self. handles returned by self stay valid.
+    pub fn meld(&mut self, other: FibonacciHeap<T, O>) -> impl Fn(Handle) -> Handle {
+        let offset = self.nodes.absorb(other.nodes, |node, offset| {
+            node.parent = node.parent.map(|p| p + offset);
+            node.child = node.child.map(|c| c + offset);
+            node.left += offset;
+            node.right += offset;
+        });
+
+        if let Some(t) = other.top {
+            let t = t + offset;
+            match self.top {
+                Some(top) => {
+                    self.splice(top, t);
+                    if self.precedes(t, top) {
+                        self.top = Some(t);
+                    }
+                },
+                None => self.top = Some(t),
+            }
+        }
+        self.len += other.len;
+
+        move |handle| Handle {
+            index: handle.index + offset,
+            generation: handle.generation,
+        }
+    }
+}
+
+// private methods
+impl<T, O: Order<T>> FibonacciHeap<T, O> {
+    fn precedes(&self, x: usize, y: usize) -> bool {
+        self.order.precedes(&self.nodes[x].item, &self.nodes[y].item)
+    }
+
+    // slot of handle, panics if it is not on the heap
+    fn position(&self, handle: Handle) -> usize {
+        match self.nodes.index_of(handle) {
+            Some(x) => x,
+            None => panic!("handle {:?} is not on the heap", handle),
+        }
+    }
+
+    // join the circular lists containing x and y
+    fn splice(&mut self, x: usize, y: usize) {
+        let x_right = self.nodes[x].right;
+        let y_left = self.nodes[y].left;
+        self.nodes[x].right = y;
+        self.nodes[y].left = x;
+        self.nodes[y_left].right = x_right;
+        self.nodes[x_right].left = y_left;
+    }
+
+    // take x out of its list, leaving it in a list of its own
+    fn unlink(&mut self, x: usize) {
+        let (left, right) = (self.nodes[x].left, self.nodes[x].right);
+        self.nodes[left].right = right;
+        self.nodes[right].left = left;
+        self.nodes[x].left = x;
+        self.nodes[x].right = x;
+    }
+
+    // add the single tree rooted at x to the root list
+    fn add_root(&mut self, x: usize) {
+        self.nodes[x].parent = None;
+        self.nodes[x].marked = false;
+        match self.top {
+            Some(top) => {
+                self.splice(top, x);
+                if self.precedes(x, top) {
+                    self.top = Some(x);
+                }
+            },
+            None => self.top = Some(x),
+        }
+    }
+
+    // make root y a child of root x
+    fn link(&mut self, y: usize, x: usize) {
+        self.unlink(y);
+        self.nodes[y].parent = Some(x);
+        self.nodes[y].marked = false;
+        match self.nodes[x].child {
+            Some(c) => self.splice(c, y),
+            None => self.nodes[x].child = Some(y),
+        }
+        self.nodes[x].degree += 1;
+    }
+
+    // link the roots of equal degree until all degrees differ, then find the new top.
+    // start is any root.
+    fn consolidate(&mut self, start: usize) {
+        let mut roots = vec![start];
+        let mut x = self.nodes[start].right;
+        while x != start {
+            roots.push(x);
+            x = self.nodes[x].right;
+        }
+
+        let mut by_degree: Vec<Option<usize>> = Vec::new();
+        for mut x in roots {
+            loop {
+                let d = self.nodes[x].degree;
+                if d >= by_degree.len() {
+                    by_degree.resize(d+1, None);
+                }
+                match by_degree[d].take() {
+                    Some(mut y) => {
+                        if self.precedes(y, x) {
+                            mem::swap(&mut x, &mut y);
+                        }
+                        self.link(y, x);
+                    },
+                    None => {
+                        by_degree[d] = Some(x);
+                        break;
+                    },
+                }
+            }
+        }
+
+        self.top = None;
+        for x in by_degree.into_iter().flatten() {
+            match self.top {
+                Some(top) if !self.precedes(x, top) => (),
+                _ => self.top = Some(x),
+            }
+        }
+    }
+
+    // move x from the children of p to the root list
+    fn cut(&mut self, x: usize, p: usize) {
+        if self.nodes[p].child == Some(x) {
+            let right = self.nodes[x].right;
+            self.nodes[p].child = if right == x { None } else { Some(right) };
+        }
+        self.unlink(x);
+        self.nodes[p].degree -= 1;
+        self.add_root(x);
+    }
+
+    // mark y when it loses its first child, cut it when it loses the second one
+    fn cascading_cut(&mut self, mut y: usize) {
+        while let Some(p) = self.nodes[y].parent {
+            if !self.nodes[y].marked {
+                self.nodes[y].marked = true;
+                return;
+            }
+            self.cut(y, p);
+            y = p;
+        }
+    }
+}
+
+impl<T, O: Order<T> + Default> Default for FibonacciHeap<T, O> {
+    fn default() -> FibonacciHeap<T, O> {
+        FibonacciHeap::with_order(O::default())
+    }
+}
+
+impl<T, O: Order<T>> PriorityQueue<T> for FibonacciHeap<T, O> {
+    fn insert(&mut self, target: T) {
+        FibonacciHeap::insert(self, target);
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        FibonacciHeap::remove(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        FibonacciHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        FibonacciHeap::len(self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut h = FibonacciHeap::new();
+        for &x in [3,7,6,2,1,5,4,8].iter() {
+            h.insert(x);
+        }
+        assert_eq!(h.len(), 8);
+        assert_eq!(h.peek(), Some(&8));
+
+        for i in (1..=8).rev() {
+            assert_eq!(h.remove(), Some(i));
+        }
+        assert_eq!(h.remove(), None);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut h: FibonacciHeap<i32, Min> = FibonacciHeap::new_min();
+        let handles: Vec<_> = (0..100).map(|i| h.insert(100 + i)).collect();
+
+        // consolidate into deeper trees, then cut nodes out of them
+        assert_eq!(h.remove(), Some(100));
+        for (i, &handle) in handles.iter().enumerate().skip(1).step_by(3) {
+            assert_eq!(h.decrease_key(handle, i as i32 - 100), 100 + i as i32);
+        }
+        assert_eq!(h.get(handles[4]), Some(&-96));
+        assert_eq!(h.decrease_key(handles[4], -96), -96);
+
+        let mut expected: Vec<i32> = (1..100).map(|i| if i % 3 == 1 { i - 100 } else { 100 + i }).collect();
+        expected.sort();
+        let removed: Vec<_> = std::iter::from_fn(|| h.remove()).collect();
+        assert_eq!(removed, expected);
+    }
+
+    #[test]
+    fn test_delete_and_stale_handles() {
+        let mut h: FibonacciHeap<i32, Min> = FibonacciHeap::new_min();
+        let handles: Vec<_> = (0..10).map(|i| h.insert(i)).collect();
+        assert_eq!(h.remove(), Some(0));
+
+        assert_eq!(h.delete(handles[5]), Some(5));
+        assert_eq!(h.delete(handles[5]), None);
+        assert_eq!(h.delete(handles[0]), None);
+        assert!(!h.contains(handles[5]));
+
+        // the slot of 5 is reused, but the old handle does not refer to the new element
+        let new = h.insert(42);
+        assert!(h.contains(new));
+        assert_eq!(h.get(handles[5]), None);
+
+        let removed: Vec<_> = std::iter::from_fn(|| h.remove()).collect();
+        assert_eq!(removed, vec![1, 2, 3, 4, 6, 7, 8, 9, 42]);
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a: FibonacciHeap<i32, Min> = FibonacciHeap::new_min();
+        let mut b: FibonacciHeap<i32, Min> = FibonacciHeap::new_min();
+        let ha: Vec<_> = (0..20).map(|i| a.insert(2 * i)).collect();
+        let hb: Vec<_> = (0..20).map(|i| b.insert(2 * i + 1)).collect();
+        a.remove();
+        b.remove();
+
+        let remap = a.meld(b);
+        assert_eq!(a.len(), 38);
+        assert_eq!(a.get(ha[3]), Some(&6));
+        assert_eq!(a.get(remap(hb[3])), Some(&7));
+        a.decrease_key(remap(hb[19]), -1);
+
+        assert_eq!(a.remove(), Some(-1));
+        assert_eq!(a.remove(), Some(2));
+        assert_eq!(a.remove(), Some(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "ordered after the current key")]
+    fn test_increase_with_decrease_key() {
+        let mut h: FibonacciHeap<i32, Min> = FibonacciHeap::new_min();
+        let handle = h.insert(3);
+        h.decrease_key(handle, 4);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut gen = rand::thread_rng();
+        let mut h: FibonacciHeap<u32, Min> = FibonacciHeap::new_min();
+        let mut live: Vec<(Handle, u32)> = Vec::new();
+
+        for _ in 0..5000 {
+            match gen.gen_range(0, 4) {
+                0 | 1 => {
+                    let key = gen.gen_range(0, 1000);
+                    live.push((h.insert(key), key));
+                },
+                2 if !live.is_empty() => {
+                    let i = gen.gen_range(0, live.len());
+                    let key = live[i].1 / 2;
+                    h.decrease_key(live[i].0, key);
+                    live[i].1 = key;
+                },
+                _ if !live.is_empty() => {
+                    if gen.gen() {
+                        let i = gen.gen_range(0, live.len());
+                        let (handle, key) = live.swap_remove(i);
+                        assert_eq!(h.delete(handle), Some(key));
+                    } else {
+                        let top = h.remove().unwrap();
+                        assert_eq!(live.iter().map(|&(_, key)| key).min(), Some(top));
+                        let i = live.iter().position(|&(handle, _)| !h.contains(handle)).unwrap();
+                        assert_eq!(live.swap_remove(i).1, top);
+                    }
+                },
+                _ => assert_eq!(h.remove(), None),
+            }
+            assert_eq!(h.len(), live.len());
+        }
+    }
+}
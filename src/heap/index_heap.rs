@@ -0,0 +1,309 @@
+use super::order::{Max, Min, Order};
+use super::sift::Sift;
+
+// an indexed priority queue associates every key with an index in [0..capacity),
+// which lets clients refer to entries already on the heap to change or delete them,
+// e.g. to decrease the tentative distance of a vertex in Dijkstra's algorithm.
+//
+// the heap holds indices rather than keys:
+// pq[p]: index of the entry at heap position p;
+// qp[i]: heap position of index i, or None if i is not on the heap(qp[pq[p]] == p);
+// keys[i]: key of index i.
+//
+// inspired by https://algs4.cs.princeton.edu/24pq/IndexMinPQ.java.html
+pub struct IndexPQ<K, O = Min> {
+    pq: Vec<usize>,
+    qp: Vec<Option<usize>>,
+    keys: Vec<Option<K>>,
+    order: O,
+}
+
+pub type IndexMinPQ<K> = IndexPQ<K, Min>;
+pub type IndexMaxPQ<K> = IndexPQ<K, Max>;
+
+impl<K, O: Order<K> + Default> IndexPQ<K, O> {
+    // an empty queue for indices in [0..capacity)
+    pub fn new(capacity: usize) -> IndexPQ<K, O> {
+        IndexPQ::with_order(capacity, O::default())
+    }
+}
+
+impl<K, O: Order<K>> IndexPQ<K, O> {
+    pub fn with_order(capacity: usize, order: O) -> IndexPQ<K, O> {
+        IndexPQ {
+            pq: Vec::with_capacity(capacity),
+            qp: vec![None; capacity],
+            keys: (0..capacity).map(|_| None).collect(),
+            order,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.qp.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        i < self.capacity() && self.qp[i].is_some()
+    }
+
+    // panics if i is out of range or already on the queue
+    pub fn insert(&mut self, i: usize, key: K) {
+        assert!(i < self.capacity(), "index {} out of range {}", i, self.capacity());
+        assert!(!self.contains(i), "index {} is already on the queue", i);
+
+        self.qp[i] = Some(self.pq.len());
+        self.pq.push(i);
+        self.keys[i] = Some(key);
+        self.swim(self.pq.len()-1);
+    }
+
+    // the index on top and its key
+    pub fn peek(&self) -> Option<(usize, &K)> {
+        self.pq.first().map(|&i| (i, self.keys[i].as_ref().unwrap()))
+    }
+
+    // remove the index on top, returning it with its key
+    pub fn remove(&mut self) -> Option<(usize, K)> {
+        let i = *self.pq.first()?;
+        let key = self.delete(i).unwrap();
+        Some((i, key))
+    }
+
+    pub fn key_of(&self, i: usize) -> Option<&K> {
+        self.keys.get(i).and_then(|k| k.as_ref())
+    }
+
+    // replace the key of i, returning the old key.
+    // panics if i is not on the queue.
+    pub fn change_key(&mut self, i: usize, key: K) -> K {
+        let p = self.position(i);
+        let old = self.keys[i].replace(key).unwrap();
+
+        // only one of them moves the entry
+        self.swim(p);
+        self.sink(self.qp[i].unwrap());
+        old
+    }
+
+    // panics if i is not on the queue, or if key is greater than the current key of i
+    pub fn decrease_key(&mut self, i: usize, key: K) -> K
+    where
+        K: PartialOrd,
+    {
+        self.position(i);
+        if key > *self.keys[i].as_ref().unwrap() {
+            panic!("decrease_key() with a greater key");
+        }
+        self.change_key(i, key)
+    }
+
+    // panics if i is not on the queue, or if key is less than the current key of i
+    pub fn increase_key(&mut self, i: usize, key: K) -> K
+    where
+        K: PartialOrd,
+    {
+        self.position(i);
+        if key < *self.keys[i].as_ref().unwrap() {
+            panic!("increase_key() with a smaller key");
+        }
+        self.change_key(i, key)
+    }
+
+    // remove i from the queue, returning its key, or None if i is not on the queue
+    pub fn delete(&mut self, i: usize) -> Option<K> {
+        if !self.contains(i) {
+            return None;
+        }
+
+        // move the last entry into the hole, then restore the heap invariant around it
+        let p = self.qp[i].unwrap();
+        let last = self.pq.len()-1;
+        self.swap(p, last);
+        self.pq.pop();
+        self.qp[i] = None;
+        if p < self.pq.len() {
+            let moved = self.pq[p];
+            self.swim(p);
+            self.sink(self.qp[moved].unwrap());
+        }
+
+        self.keys[i].take()
+    }
+
+    // heap position of i, panics if i is not on the queue
+    fn position(&self, i: usize) -> usize {
+        match self.qp.get(i) {
+            Some(&Some(p)) => p,
+            _ => panic!("index {} is not on the queue", i),
+        }
+    }
+}
+
+impl<K, O: Order<K>> Sift for IndexPQ<K, O> {
+    fn heap_len(&self) -> usize {
+        self.pq.len()
+    }
+
+    fn precedes(&self, i: usize, j: usize) -> bool {
+        let a = self.keys[self.pq[i]].as_ref().unwrap();
+        let b = self.keys[self.pq[j]].as_ref().unwrap();
+        self.order.precedes(a, b)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.pq.swap(i, j);
+        self.qp[self.pq[i]] = Some(i);
+        self.qp[self.pq[j]] = Some(j);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let words = ["it", "was", "the", "best", "of", "times", "it", "was", "the", "worst"];
+        let mut pq = IndexMinPQ::new(words.len());
+        for (i, &w) in words.iter().enumerate() {
+            pq.insert(i, w);
+        }
+        assert_eq!(pq.len(), words.len());
+        assert_eq!(pq.peek(), Some((3, &"best")));
+
+        let mut sorted: Vec<_> = words.to_vec();
+        sorted.sort();
+        for &w in sorted.iter() {
+            let (i, key) = pq.remove().unwrap();
+            assert_eq!(key, w);
+            assert_eq!(words[i], w);
+            assert!(!pq.contains(i));
+        }
+        assert!(pq.is_empty());
+        assert_eq!(pq.remove(), None);
+    }
+
+    #[test]
+    fn test_max() {
+        let mut pq = IndexMaxPQ::new(4);
+        pq.insert(2, 1.5);
+        pq.insert(0, 7.0);
+        pq.insert(3, -2.0);
+
+        assert_eq!(pq.remove(), Some((0, 7.0)));
+        assert_eq!(pq.remove(), Some((2, 1.5)));
+        assert_eq!(pq.remove(), Some((3, -2.0)));
+    }
+
+    #[test]
+    fn test_change_key() {
+        let mut pq = IndexMinPQ::new(5);
+        for i in 0..5 {
+            pq.insert(i, 10 * i);
+        }
+
+        assert_eq!(pq.decrease_key(4, 5), 40);
+        assert_eq!(pq.increase_key(0, 25), 0);
+        assert_eq!(pq.change_key(2, 100), 20);
+        assert_eq!(pq.key_of(4), Some(&5));
+        assert_eq!(pq.key_of(7), None);
+
+        let order: Vec<_> = std::iter::from_fn(|| pq.remove()).collect();
+        assert_eq!(order, vec![(4, 5), (1, 10), (0, 25), (3, 30), (2, 100)]);
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut pq = IndexMinPQ::new(6);
+        for (i, &k) in [5, 3, 8, 1, 9, 2].iter().enumerate() {
+            pq.insert(i, k);
+        }
+
+        assert_eq!(pq.delete(3), Some(1));
+        assert_eq!(pq.delete(3), None);
+        assert_eq!(pq.delete(10), None);
+        assert!(!pq.contains(3));
+        assert_eq!(pq.len(), 5);
+
+        // a deleted index can be reused
+        pq.insert(3, 4);
+        let keys: Vec<_> = std::iter::from_fn(|| pq.remove()).map(|(_, k)| k).collect();
+        assert_eq!(keys, vec![2, 3, 4, 5, 8, 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "already on the queue")]
+    fn test_insert_twice() {
+        let mut pq = IndexMinPQ::new(2);
+        pq.insert(1, 0);
+        pq.insert(1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "decrease_key() with a greater key")]
+    fn test_decrease_key_with_greater_key() {
+        let mut pq = IndexMinPQ::new(1);
+        pq.insert(0, 3);
+        pq.decrease_key(0, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "not on the queue")]
+    fn test_change_missing_key() {
+        let mut pq: IndexMinPQ<i32> = IndexMinPQ::new(1);
+        pq.change_key(0, 1);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let n = 200;
+        let mut gen = rand::thread_rng();
+        let mut pq = IndexMinPQ::new(n);
+        let mut expected: Vec<Option<u32>> = vec![None; n];
+
+        for _ in 0..5000 {
+            let i = gen.gen_range(0, n);
+            let key = gen.gen_range(0, 1000);
+            match gen.gen_range(0, 4) {
+                0 => {
+                    if !pq.contains(i) {
+                        pq.insert(i, key);
+                        expected[i] = Some(key);
+                    }
+                },
+                1 => {
+                    if pq.contains(i) {
+                        pq.change_key(i, key);
+                        expected[i] = Some(key);
+                    }
+                },
+                2 => assert_eq!(pq.delete(i), expected[i].take()),
+                _ => {
+                    let top = expected.iter().filter_map(|&k| k).min();
+                    match pq.remove() {
+                        Some((i, key)) => {
+                            assert_eq!(Some(key), top);
+                            assert_eq!(expected[i].take(), Some(key));
+                        },
+                        None => assert_eq!(top, None),
+                    }
+                },
+            }
+            assert_eq!(pq.len(), expected.iter().filter(|k| k.is_some()).count());
+        }
+    }
+}
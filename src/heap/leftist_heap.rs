@@ -0,0 +1,215 @@
+use std::mem::swap;
+
+use super::order::{Max, Min, Order};
+use super::PriorityQueue;
+
+// a leftist heap is a heap ordered binary tree where every node satisfies:
+// rank(left) >= rank(right), where rank is the length of the right spine(the s-value).
+// the right spine is therefore at most log(n+1) long, and two heaps can be melded in
+// O(logn) by merging their right spines. insert and remove are implemented with meld.
+//
+// references:
+// https://en.wikipedia.org/wiki/Leftist_tree
+// Okasaki, Purely Functional Data Structures, 3.1
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    item: T,
+    rank: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+fn rank<T>(link: &Link<T>) -> usize {
+    match link {
+        Some(node) => node.rank,
+        None => 0,
+    }
+}
+
+// merge the right spines of a and b, swapping children on the way back up where
+// the leftist property is violated. the recursion depth is rank(a) + rank(b).
+fn merge<T, O: Order<T>>(a: Link<T>, b: Link<T>, order: &O) -> Link<T> {
+    match (a, b) {
+        (None, link) | (link, None) => link,
+        (Some(mut a), Some(mut b)) => {
+            if order.precedes(&b.item, &a.item) {
+                swap(&mut a, &mut b);
+            }
+
+            a.right = merge(a.right.take(), Some(b), order);
+            if rank(&a.left) < rank(&a.right) {
+                swap(&mut a.left, &mut a.right);
+            }
+            a.rank = rank(&a.right) + 1;
+            Some(a)
+        },
+    }
+}
+
+pub struct LeftistHeap<T, O = Max> {
+    root: Link<T>,
+    len: usize,
+    order: O,
+}
+
+impl<T: PartialOrd> LeftistHeap<T, Max> {
+    pub fn new() -> LeftistHeap<T> {
+        LeftistHeap::with_order(Max)
+    }
+}
+
+impl<T: PartialOrd> LeftistHeap<T, Min> {
+    pub fn new_min() -> LeftistHeap<T, Min> {
+        LeftistHeap::with_order(Min)
+    }
+}
+
+impl<T, O: Order<T>> LeftistHeap<T, O> {
+    pub fn with_order(order: O) -> LeftistHeap<T, O> {
+        LeftistHeap {
+            root: None,
+            len: 0,
+            order,
+        }
+    }
+
+    pub fn insert(&mut self, target: T) {
+        let node = Box::new(Node {
+            item: target,
+            rank: 1,
+            left: None,
+            right: None,
+        });
+        self.root = merge(self.root.take(), Some(node), &self.order);
+        self.len += 1;
+    }
+
+    pub fn remove(&mut self) -> Option<T> {
+        let node = *self.root.take()?;
+        self.root = merge(node.left, node.right, &self.order);
+        self.len -= 1;
+        Some(node.item)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // move all elements of other into self in O(logn)
+    pub fn meld(&mut self, mut other: LeftistHeap<T, O>) {
+        self.root = merge(self.root.take(), other.root.take(), &self.order);
+        self.len += other.len;
+    }
+}
+
+impl<T, O: Order<T> + Default> Default for LeftistHeap<T, O> {
+    fn default() -> LeftistHeap<T, O> {
+        LeftistHeap::with_order(O::default())
+    }
+}
+
+// the left spine can be O(n) long, drop the nodes iteratively to not overflow the stack
+impl<T, O> Drop for LeftistHeap<T, O> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T, O: Order<T>> PriorityQueue<T> for LeftistHeap<T, O> {
+    fn insert(&mut self, target: T) {
+        LeftistHeap::insert(self, target);
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        LeftistHeap::remove(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        LeftistHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        LeftistHeap::len(self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // check heap order and the leftist property, returns the number of nodes
+    fn assert_leftist<T, O: Order<T>>(link: &Link<T>, order: &O) -> usize {
+        match link {
+            Some(node) => {
+                for child in [&node.left, &node.right].iter().copied().flatten() {
+                    assert!(!order.precedes(&child.item, &node.item));
+                }
+                assert!(rank(&node.left) >= rank(&node.right));
+                assert_eq!(node.rank, rank(&node.right) + 1);
+                assert_leftist(&node.left, order) + assert_leftist(&node.right, order) + 1
+            },
+            None => 0,
+        }
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut h = LeftistHeap::new();
+        for &x in [3,7,6,2,1,5,4,8].iter() {
+            h.insert(x);
+        }
+        assert_eq!(assert_leftist(&h.root, &h.order), 8);
+        assert_eq!(h.peek(), Some(&8));
+
+        for i in (1..=8).rev() {
+            assert_eq!(h.remove(), Some(i));
+        }
+        assert_eq!(h.remove(), None);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = LeftistHeap::new_min();
+        let mut b = LeftistHeap::new_min();
+        for i in 0..50 {
+            a.insert(2 * i);
+            b.insert(2 * i + 1);
+        }
+
+        a.meld(b);
+        assert_eq!(a.len(), 100);
+        assert_eq!(assert_leftist(&a.root, &a.order), 100);
+        for i in 0..100 {
+            assert_eq!(a.remove(), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_long_spine() {
+        // decreasing keys build a left spine of length n in a min heap
+        let mut h = LeftistHeap::new_min();
+        for i in (0..100_000).rev() {
+            h.insert(i);
+        }
+        assert_eq!(h.peek(), Some(&0));
+    }
+}
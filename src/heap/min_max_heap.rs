@@ -0,0 +1,310 @@
+use std::iter::FromIterator;
+use std::slice;
+
+// a min-max heap is a complete binary tree in level order(like Heap) whose levels
+// alternate between min levels and max levels, starting with a min level at the root:
+// every node on a min level is smaller than or equal to all of its descendants, and every
+// node on a max level is greater than or equal to all of its descendants.
+// the smallest element is therefore the root, and the largest one of its two children.
+//
+// insert, pop_min and pop_max take O(logn), peek_min and peek_max O(1).
+//
+// references:
+// https://en.wikipedia.org/wiki/Min-max_heap
+// Atkinson, Sack, Santoro and Strothotte, Min-Max Heaps and Generalized Priority Queues (1986)
+pub struct MinMaxHeap<T> {
+    v: Vec<T>,
+}
+
+fn less<T: PartialOrd>(a: &T, b: &T) -> bool {
+    a < b
+}
+
+fn greater<T: PartialOrd>(a: &T, b: &T) -> bool {
+    a > b
+}
+
+// the root is on level 0
+fn is_min_level(i: usize) -> bool {
+    (i+1).ilog2() & 1 == 0
+}
+
+impl<T: PartialOrd> MinMaxHeap<T> {
+    pub fn new() -> MinMaxHeap<T> {
+        MinMaxHeap { v: Vec::new() }
+    }
+
+    // bottom-up construction in O(n), like Heap::from
+    pub fn from(v: Vec<T>) -> MinMaxHeap<T> {
+        let mut heap = MinMaxHeap { v };
+        for i in (0..heap.v.len()/2).rev() {
+            heap.trickle_down(i);
+        }
+        heap
+    }
+
+    pub fn insert(&mut self, target: T) {
+        self.v.push(target);
+        self.bubble_up(self.v.len()-1);
+    }
+
+    pub fn peek_min(&self) -> Option<&T> {
+        self.v.first()
+    }
+
+    pub fn peek_max(&self) -> Option<&T> {
+        self.max_index().map(|i| &self.v[i])
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.pop_at(0)
+    }
+
+    pub fn pop_max(&mut self) -> Option<T> {
+        let i = self.max_index()?;
+        self.pop_at(i)
+    }
+
+    pub fn len(&self) -> usize {
+        self.v.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.v.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.v.clear();
+    }
+
+    // iterate over all elements in arbitrary order
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.v.iter()
+    }
+
+    // the underlying vector, in arbitrary order
+    pub fn into_vec(self) -> Vec<T> {
+        self.v
+    }
+}
+
+// private methods
+impl<T: PartialOrd> MinMaxHeap<T> {
+    fn max_index(&self) -> Option<usize> {
+        match self.v.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ => Some(if self.v[2] > self.v[1] { 2 } else { 1 }),
+        }
+    }
+
+    // remove the element at i, moving the last element into its place
+    fn pop_at(&mut self, i: usize) -> Option<T> {
+        if i >= self.v.len() {
+            return None;
+        }
+        let res = self.v.swap_remove(i);
+        if i < self.v.len() {
+            self.trickle_down(i);
+        }
+        Some(res)
+    }
+
+    fn trickle_down(&mut self, i: usize) {
+        if is_min_level(i) {
+            self.trickle_down_by(i, less);
+        } else {
+            self.trickle_down_by(i, greater);
+        }
+    }
+
+    // sink v[i] through the levels of its kind(min or max), where precedes says which
+    // element belongs closer to the root on those levels
+    fn trickle_down_by(&mut self, mut i: usize, precedes: fn(&T, &T) -> bool) {
+        let n = self.v.len();
+        loop {
+            // m: the preceding one of the children and grandchildren of i
+            let children = (2*i+1..=2*i+2).filter(|&c| c < n);
+            let grandchildren = (4*i+3..=4*i+6).filter(|&c| c < n);
+            let m = match children.chain(grandchildren).reduce(|m, c| {
+                if precedes(&self.v[c], &self.v[m]) { c } else { m }
+            }) {
+                Some(m) => m,
+                None => return,
+            };
+
+            if !precedes(&self.v[m], &self.v[i]) {
+                return;
+            }
+            self.v.swap(m, i);
+            if m <= 2*i+2 {
+                // a child is on a level of the other kind, and has no descendants left to check
+                return;
+            }
+
+            // the element moved down to m may not fit under the parent of m,
+            // which is on a level of the other kind
+            let parent = (m-1)/2;
+            if precedes(&self.v[parent], &self.v[m]) {
+                self.v.swap(m, parent);
+            }
+            i = m;
+        }
+    }
+
+    fn bubble_up(&mut self, i: usize) {
+        if i == 0 {
+            return;
+        }
+
+        let parent = (i-1)/2;
+        if is_min_level(i) {
+            if self.v[i] > self.v[parent] {
+                self.v.swap(i, parent);
+                self.bubble_up_by(parent, greater);
+            } else {
+                self.bubble_up_by(i, less);
+            }
+        } else if self.v[i] < self.v[parent] {
+            self.v.swap(i, parent);
+            self.bubble_up_by(parent, less);
+        } else {
+            self.bubble_up_by(i, greater);
+        }
+    }
+
+    // swim v[i] through the levels of its kind, i.e. from grandparent to grandparent
+    fn bubble_up_by(&mut self, mut i: usize, precedes: fn(&T, &T) -> bool) {
+        while i >= 3 {
+            let grandparent = ((i-1)/2 - 1)/2;
+            if !precedes(&self.v[i], &self.v[grandparent]) {
+                break;
+            }
+            self.v.swap(i, grandparent);
+            i = grandparent;
+        }
+    }
+}
+
+impl<T: PartialOrd> Default for MinMaxHeap<T> {
+    fn default() -> MinMaxHeap<T> {
+        MinMaxHeap::new()
+    }
+}
+
+impl<T: PartialOrd> FromIterator<T> for MinMaxHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> MinMaxHeap<T> {
+        MinMaxHeap::from(iter.into_iter().collect())
+    }
+}
+
+impl<T: PartialOrd> Extend<T> for MinMaxHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.insert(x);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    // every node is on the right side of all its descendants
+    fn assert_min_max<T: Ord>(h: &MinMaxHeap<T>) {
+        for i in 1..h.v.len() {
+            let mut a = (i-1)/2;
+            loop {
+                if is_min_level(a) {
+                    assert!(h.v[i] >= h.v[a]);
+                } else {
+                    assert!(h.v[i] <= h.v[a]);
+                }
+                if a == 0 {
+                    break;
+                }
+                a = (a-1)/2;
+            }
+        }
+    }
+
+    #[test]
+    fn test_levels() {
+        let levels: Vec<_> = (0..16).map(is_min_level).collect();
+        let mut expected = vec![true, false, false];
+        expected.extend(vec![true; 4]);
+        expected.extend(vec![false; 8]);
+        expected.push(true);
+        assert_eq!(levels, expected);
+    }
+
+    #[test]
+    fn test_pop_both_ends() {
+        let mut h = MinMaxHeap::new();
+        for &x in [3,7,6,2,1,5,4,8].iter() {
+            h.insert(x);
+            assert_min_max(&h);
+        }
+        assert_eq!(h.peek_min(), Some(&1));
+        assert_eq!(h.peek_max(), Some(&8));
+
+        for i in 0..4 {
+            assert_eq!(h.pop_min(), Some(1 + i));
+            assert_eq!(h.pop_max(), Some(8 - i));
+            assert_min_max(&h);
+        }
+        assert_eq!(h.pop_min(), None);
+        assert_eq!(h.pop_max(), None);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_from() {
+        let mut gen = rand::thread_rng();
+        for &len in [0, 1, 2, 3, 7, 100, 1000].iter() {
+            let v: Vec<u32> = (0..len).map(|_| gen.gen_range(0, 100)).collect();
+            let mut expected = v.clone();
+            expected.sort();
+
+            let mut h = MinMaxHeap::from(v);
+            assert_min_max(&h);
+            assert_eq!(h.len(), len);
+            assert_eq!(h.peek_min(), expected.first());
+            assert_eq!(h.peek_max(), expected.last());
+
+            let descending: Vec<_> = std::iter::from_fn(|| h.pop_max()).collect();
+            expected.reverse();
+            assert_eq!(descending, expected);
+        }
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut gen = rand::thread_rng();
+        let mut h: MinMaxHeap<u32> = (0..50).map(|_| gen.gen_range(0, 1000)).collect();
+        let mut expected: Vec<u32> = h.iter().cloned().collect();
+        expected.sort();
+
+        for _ in 0..5000 {
+            match gen.gen_range(0, 3) {
+                0 => {
+                    let x = gen.gen_range(0, 1000);
+                    h.insert(x);
+                    let i = expected.iter().position(|&y| y > x).unwrap_or(expected.len());
+                    expected.insert(i, x);
+                },
+                1 => assert_eq!(h.pop_min(), if expected.is_empty() { None } else { Some(expected.remove(0)) }),
+                _ => assert_eq!(h.pop_max(), expected.pop()),
+            }
+            assert_min_max(&h);
+            assert_eq!(h.len(), expected.len());
+        }
+    }
+}
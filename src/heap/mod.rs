@@ -1 +1,103 @@
 pub mod binary_heap;
+pub mod binomial_heap;
+pub mod concurrent;
+pub mod dary_heap;
+pub mod fibonacci_heap;
+pub mod index_heap;
+pub mod leftist_heap;
+pub mod min_max_heap;
+pub mod monotone;
+pub mod order;
+pub mod pairing_heap;
+pub mod persistent;
+pub mod running_median;
+pub mod scheduler;
+pub mod skew_heap;
+pub mod top_k;
+
+mod arena;
+mod sift;
+
+// operations shared by all priority queues in this module, so that algorithms
+// can be written once and run on whichever heap fits the workload.
+pub trait PriorityQueue<T> {
+    fn insert(&mut self, target: T);
+
+    // remove the top element
+    fn remove(&mut self) -> Option<T>;
+
+    fn peek(&self) -> Option<&T>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// refers to an element of a FibonacciHeap or a PairingHeap, returned by insert.
+// a handle stays valid until its element is removed; handles of removed elements are
+// detected rather than silently referring to a newer element in the same slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: usize,
+    generation: usize,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::PriorityQueue;
+    use super::binary_heap::{Heap, StableHeap};
+    use super::binomial_heap::BinomialHeap;
+    use super::dary_heap::DaryHeap;
+    use super::fibonacci_heap::FibonacciHeap;
+    use super::leftist_heap::LeftistHeap;
+    use super::order::Min;
+    use super::pairing_heap::PairingHeap;
+    use super::skew_heap::SkewHeap;
+
+    // written against the trait only
+    fn heap_sort<Q: PriorityQueue<u32>>(mut q: Q, v: &[u32]) -> Vec<u32> {
+        for &x in v {
+            q.insert(x);
+        }
+        assert_eq!(q.len(), v.len());
+
+        let mut res = Vec::with_capacity(v.len());
+        while let Some(&top) = q.peek() {
+            assert_eq!(q.remove(), Some(top));
+            res.push(top);
+        }
+        assert!(q.is_empty());
+        res
+    }
+
+    #[test]
+    fn test_priority_queues() {
+        let mut gen = rand::thread_rng();
+        let v: Vec<u32> = (0..1000).map(|_| gen.gen_range(0, 500)).collect();
+        let mut expected = v.clone();
+        expected.sort();
+
+        assert_eq!(heap_sort(Heap::new_min(), &v), expected);
+        assert_eq!(heap_sort(StableHeap::new_min(), &v), expected);
+        assert_eq!(heap_sort(DaryHeap::<u32, 4, Min>::new_min(), &v), expected);
+        assert_eq!(heap_sort(BinomialHeap::new_min(), &v), expected);
+        assert_eq!(heap_sort(LeftistHeap::new_min(), &v), expected);
+        assert_eq!(heap_sort(SkewHeap::new_min(), &v), expected);
+        assert_eq!(heap_sort(FibonacciHeap::new_min(), &v), expected);
+        assert_eq!(heap_sort(PairingHeap::new_min(), &v), expected);
+
+        expected.reverse();
+        assert_eq!(heap_sort(Heap::new(), &v), expected);
+        assert_eq!(heap_sort(BinomialHeap::new(), &v), expected);
+        assert_eq!(heap_sort(FibonacciHeap::new(), &v), expected);
+        assert_eq!(heap_sort(PairingHeap::new(), &v), expected);
+    }
+}
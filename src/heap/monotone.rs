@@ -0,0 +1,340 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+
+// monotone priority queues for integer keys: the removed keys never decrease, so every
+// inserted key must be at least the last removed one. this holds in Dijkstra's algorithm
+// (and in event simulations), where it allows much faster queues than comparison based heaps.
+//
+// both queues remove the smallest key first, and remove elements with equal keys in
+// arbitrary order.
+
+// an insert with a key smaller than the last removed key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonotonicityError {
+    pub key: u64,
+    pub last: u64,
+}
+
+impl fmt::Display for MonotonicityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key {} is smaller than the last removed key {}", self.key, self.last)
+    }
+}
+
+impl Error for MonotonicityError {}
+
+////////////////////////////////////////////////////////////////////////////////
+// radix heap
+////////////////////////////////////////////////////////////////////////////////
+
+// a radix heap puts every key into the bucket of the highest bit in which it differs from
+// the last removed key: bucket 0 holds keys equal to last, bucket i holds keys that
+// differ from last first in bit i-1. when bucket 0 runs empty, the first nonempty bucket
+// is emptied into the lower buckets relative to its smallest key. a key only ever moves to
+// lower buckets, so remove takes amortized O(log C) for keys up to C, i.e. at most 64.
+//
+// reference: Ahuja, Mehlhorn, Orlin and Tarjan, Faster Algorithms for the Shortest Path Problem (1990)
+pub struct RadixHeap<V> {
+    buckets: Vec<Vec<(u64, V)>>,
+    last: u64,
+    len: usize,
+}
+
+impl<V> RadixHeap<V> {
+    pub fn new() -> RadixHeap<V> {
+        RadixHeap {
+            buckets: (0..=64).map(|_| Vec::new()).collect(),
+            last: 0,
+            len: 0,
+        }
+    }
+
+    pub fn insert(&mut self, key: u64, value: V) -> Result<(), MonotonicityError> {
+        if key < self.last {
+            return Err(MonotonicityError { key, last: self.last });
+        }
+
+        let b = self.bucket(key);
+        self.buckets[b].push((key, value));
+        self.len += 1;
+        Ok(())
+    }
+
+    // remove an element with the smallest key
+    pub fn remove(&mut self) -> Option<(u64, V)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        if self.buckets[0].is_empty() {
+            let i = self.buckets.iter().position(|b| !b.is_empty()).unwrap();
+            let items = mem::take(&mut self.buckets[i]);
+            self.last = items.iter().map(|&(key, _)| key).min().unwrap();
+            for (key, value) in items {
+                let b = self.bucket(key);
+                self.buckets[b].push((key, value));
+            }
+        }
+
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+
+    // an element with the smallest key, without removing it. O(n) in the worst case,
+    // because the smallest key is only known for bucket 0.
+    pub fn peek(&self) -> Option<(u64, &V)> {
+        let bucket = self.buckets.iter().find(|b| !b.is_empty())?;
+        bucket.iter()
+            .min_by_key(|&&(key, _)| key)
+            .map(|(key, value)| (*key, value))
+    }
+
+    // the last removed key, which bounds the keys that can be inserted
+    pub fn last(&self) -> u64 {
+        self.last
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // remove all elements, the last removed key is kept
+    pub fn clear(&mut self) {
+        for b in self.buckets.iter_mut() {
+            b.clear();
+        }
+        self.len = 0;
+    }
+
+    fn bucket(&self, key: u64) -> usize {
+        (64 - (key ^ self.last).leading_zeros()) as usize
+    }
+}
+
+impl<V> Default for RadixHeap<V> {
+    fn default() -> RadixHeap<V> {
+        RadixHeap::new()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// bucket queue
+////////////////////////////////////////////////////////////////////////////////
+
+// Dial's bucket queue, for keys that are never more than max_span above the last removed
+// key, e.g. Dijkstra's algorithm with integer edge weights of at most max_span.
+// all keys on the queue are in [last, last + max_span], so max_span + 1 buckets used as a
+// circular array hold exactly one key each. insert takes O(1), and remove scans forward
+// from the last removed key, which takes O(max_span) in the worst case but O(1) amortized
+// when the keys are dense.
+pub struct BucketQueue<V> {
+    buckets: Vec<VecDeque<V>>,
+    last: u64,
+    len: usize,
+}
+
+impl<V> BucketQueue<V> {
+    pub fn new(max_span: usize) -> BucketQueue<V> {
+        BucketQueue {
+            buckets: (0..=max_span).map(|_| VecDeque::new()).collect(),
+            last: 0,
+            len: 0,
+        }
+    }
+
+    pub fn max_span(&self) -> usize {
+        self.buckets.len() - 1
+    }
+
+    // panics if key is more than max_span above the last removed key
+    pub fn insert(&mut self, key: u64, value: V) -> Result<(), MonotonicityError> {
+        if key < self.last {
+            return Err(MonotonicityError { key, last: self.last });
+        }
+        assert!(key - self.last <= self.max_span() as u64,
+            "key {} is more than {} above the last removed key {}", key, self.max_span(), self.last);
+
+        let b = self.bucket(key);
+        self.buckets[b].push_back(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    // remove an element with the smallest key, elements with equal keys are removed in
+    // insertion order
+    pub fn remove(&mut self) -> Option<(u64, V)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        loop {
+            let b = self.bucket(self.last);
+            if let Some(value) = self.buckets[b].pop_front() {
+                self.len -= 1;
+                return Some((self.last, value));
+            }
+            self.last += 1;
+        }
+    }
+
+    // an element with the smallest key, without removing it. O(max_span).
+    pub fn peek(&self) -> Option<(u64, &V)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        (self.last..).find_map(|key| {
+            self.buckets[self.bucket(key)].front().map(|value| (key, value))
+        })
+    }
+
+    // the last removed key, which bounds the keys that can be inserted
+    pub fn last(&self) -> u64 {
+        self.last
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // remove all elements, the last removed key is kept
+    pub fn clear(&mut self) {
+        for b in self.buckets.iter_mut() {
+            b.clear();
+        }
+        self.len = 0;
+    }
+
+    fn bucket(&self, key: u64) -> usize {
+        (key % self.buckets.len() as u64) as usize
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    use crate::heap::binary_heap::Heap;
+
+    // Dijkstra-like workload: every inserted key is the last removed key plus at most span.
+    // both queues are checked against a binary heap.
+    fn check_monotone<I, R>(span: u64, mut insert: I, mut remove: R)
+    where
+        I: FnMut(u64, usize),
+        R: FnMut() -> Option<(u64, usize)>,
+    {
+        let mut gen = rand::thread_rng();
+        let mut expected = Heap::new_min();
+        let mut last = 0;
+
+        for i in 0..5000 {
+            if gen.gen_range(0, 3) > 0 {
+                let key = last + gen.gen_range(0, span + 1);
+                insert(key, i);
+                expected.insert(key);
+            } else {
+                let removed = remove();
+                assert_eq!(removed.map(|(key, _)| key), expected.remove());
+                if let Some((key, _)) = removed {
+                    last = key;
+                }
+            }
+        }
+        while let Some((key, _)) = remove() {
+            assert_eq!(Some(key), expected.remove());
+        }
+        assert!(expected.is_empty());
+    }
+
+    #[test]
+    fn test_radix_heap() {
+        let mut h = RadixHeap::new();
+        for &key in [8, 3, 1_000_000, 3, 0, 42].iter() {
+            h.insert(key, key * 2).unwrap();
+        }
+        assert_eq!(h.len(), 6);
+        assert_eq!(h.peek(), Some((0, &0)));
+
+        let keys: Vec<_> = std::iter::from_fn(|| h.remove()).map(|(key, _)| key).collect();
+        assert_eq!(keys, vec![0, 3, 3, 8, 42, 1_000_000]);
+        assert_eq!(h.last(), 1_000_000);
+
+        h.insert(u64::MAX, 0).unwrap();
+        assert_eq!(h.remove(), Some((u64::MAX, 0)));
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_radix_heap_monotone() {
+        let h = std::cell::RefCell::new(RadixHeap::new());
+        check_monotone(1 << 20,
+            |key, i| h.borrow_mut().insert(key, i).unwrap(),
+            || h.borrow_mut().remove());
+    }
+
+    #[test]
+    fn test_bucket_queue() {
+        let mut q = BucketQueue::new(10);
+        for (i, &key) in [7, 3, 10, 3, 0].iter().enumerate() {
+            q.insert(key, i).unwrap();
+        }
+        assert_eq!(q.peek(), Some((0, &4)));
+        assert_eq!(q.remove(), Some((0, 4)));
+        // equal keys come out in insertion order
+        assert_eq!(q.remove(), Some((3, 1)));
+        assert_eq!(q.remove(), Some((3, 3)));
+
+        // the buckets wrap around
+        q.insert(13, 5).unwrap();
+        let keys: Vec<_> = std::iter::from_fn(|| q.remove()).map(|(key, _)| key).collect();
+        assert_eq!(keys, vec![7, 10, 13]);
+        assert_eq!(q.peek(), None);
+    }
+
+    #[test]
+    fn test_bucket_queue_monotone() {
+        let q = std::cell::RefCell::new(BucketQueue::new(100));
+        check_monotone(100,
+            |key, i| q.borrow_mut().insert(key, i).unwrap(),
+            || q.borrow_mut().remove());
+    }
+
+    #[test]
+    fn test_monotonicity_error() {
+        let mut h = RadixHeap::new();
+        h.insert(5, ()).unwrap();
+        h.remove();
+        let err = h.insert(4, ()).unwrap_err();
+        assert_eq!(err, MonotonicityError { key: 4, last: 5 });
+        assert_eq!(err.to_string(), "key 4 is smaller than the last removed key 5");
+        assert!(h.insert(5, ()).is_ok());
+
+        let mut q = BucketQueue::new(3);
+        q.insert(2, ()).unwrap();
+        q.remove();
+        assert_eq!(q.insert(1, ()), Err(MonotonicityError { key: 1, last: 2 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "more than 3 above")]
+    fn test_bucket_queue_span() {
+        let mut q = BucketQueue::new(3);
+        q.insert(4, ()).unwrap();
+    }
+}
@@ -0,0 +1,68 @@
+use std::cmp::Ordering;
+
+// an order decides which of two elements is closer to the top of a heap.
+// heaps only ever ask whether one element strictly precedes another, which keeps
+// elements that are only PartialOrd (e.g. floats) usable.
+pub trait Order<T> {
+    // a should be placed above b
+    fn precedes(&self, a: &T, b: &T) -> bool;
+}
+
+// the largest element is on top
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Max;
+
+// the smallest element is on top
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Min;
+
+// the greatest element according to the comparator is on top,
+// e.g. ByComparator(|a, b| b.cmp(a)) behaves like Min.
+#[derive(Debug, Clone, Copy)]
+pub struct ByComparator<F>(pub F);
+
+// the element with the largest key is on top
+#[derive(Debug, Clone, Copy)]
+pub struct ByKey<F>(pub F);
+
+// the opposite of the wrapped order, e.g. Reversed(Max) behaves like Min.
+// elements that O considers equal stay equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Reversed<O>(pub O);
+
+impl<T: PartialOrd> Order<T> for Max {
+    fn precedes(&self, a: &T, b: &T) -> bool {
+        a > b
+    }
+}
+
+impl<T: PartialOrd> Order<T> for Min {
+    fn precedes(&self, a: &T, b: &T) -> bool {
+        a < b
+    }
+}
+
+impl<T, F> Order<T> for ByComparator<F>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    fn precedes(&self, a: &T, b: &T) -> bool {
+        (self.0)(a, b) == Ordering::Greater
+    }
+}
+
+impl<T, K, F> Order<T> for ByKey<F>
+where
+    F: Fn(&T) -> K,
+    K: PartialOrd,
+{
+    fn precedes(&self, a: &T, b: &T) -> bool {
+        (self.0)(a) > (self.0)(b)
+    }
+}
+
+impl<T, O: Order<T>> Order<T> for Reversed<O> {
+    fn precedes(&self, a: &T, b: &T) -> bool {
+        self.0.precedes(b, a)
+    }
+}
@@ -0,0 +1,343 @@
+use std::mem;
+
+use super::arena::Arena;
+use super::order::{Max, Min, Order};
+use super::{Handle, PriorityQueue};
+
+// a pairing heap is a single heap ordered multiway tree. two trees are melded by making
+// the root that does not precede the other the leftmost child of the other, and
+// removing the root melds its children in two passes: first in pairs from left to right,
+// then the pairs from right to left into a single tree.
+//
+// insert, meld and peek take O(1), remove O(logn) amortized. decrease_key cuts the
+// subtree from its parent and melds it with the root; it is o(logn) amortized and in
+// practice usually faster than a fibonacci heap, whose constant factors are large.
+//
+// every node keeps its leftmost child and its right sibling, plus a back link to its left
+// sibling(or its parent, for a leftmost child) so that it can be cut in O(1).
+//
+// references:
+// https://en.wikipedia.org/wiki/Pairing_heap
+// Fredman, Sedgewick, Sleator and Tarjan, The Pairing Heap: A New Form of Self-Adjusting Heap (1986)
+
+struct Node<T> {
+    item: T,
+    child: Option<usize>,
+    next: Option<usize>,
+    prev: Option<usize>,
+}
+
+pub struct PairingHeap<T, O = Max> {
+    nodes: Arena<Node<T>>,
+    root: Option<usize>,
+    len: usize,
+    order: O,
+}
+
+impl<T: PartialOrd> PairingHeap<T, Max> {
+    pub fn new() -> PairingHeap<T> {
+        PairingHeap::with_order(Max)
+    }
+}
+
+impl<T: PartialOrd> PairingHeap<T, Min> {
+    pub fn new_min() -> PairingHeap<T, Min> {
+        PairingHeap::with_order(Min)
+    }
+}
+
+impl<T, O: Order<T>> PairingHeap<T, O> {
+    pub fn with_order(order: O) -> PairingHeap<T, O> {
+        PairingHeap {
+            nodes: Arena::new(),
+            root: None,
+            len: 0,
+            order,
+        }
+    }
+
+    // O(1), the handle can be used to decrease the key of target or delete it later
+    pub fn insert(&mut self, target: T) -> Handle {
+        let handle = self.nodes.insert(Node {
+            item: target,
+            child: None,
+            next: None,
+            prev: None,
+        });
+        self.root = self.link(self.root, Some(handle.index));
+        self.len += 1;
+        handle
+    }
+
+    pub fn remove(&mut self) -> Option<T> {
+        let root = self.root?;
+        let child = self.nodes[root].child.take();
+        self.root = self.combine(child);
+        self.len -= 1;
+        Some(self.nodes.remove(root).item)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.map(|x| &self.nodes[x].item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.nodes.index_of(handle).is_some()
+    }
+
+    // the element handle refers to, or None if it has been removed
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.nodes.index_of(handle).map(|x| &self.nodes[x].item)
+    }
+
+    // replace the element handle refers to with one that is ordered at least as close
+    // to the top, returning the old element.
+    // panics if handle is not on the heap, or if target is ordered after the old element.
+    pub fn decrease_key(&mut self, handle: Handle, target: T) -> T {
+        let x = match self.nodes.index_of(handle) {
+            Some(x) => x,
+            None => panic!("handle {:?} is not on the heap", handle),
+        };
+        if self.order.precedes(&self.nodes[x].item, &target) {
+            panic!("decrease_key() with a key ordered after the current key");
+        }
+        let old = mem::replace(&mut self.nodes[x].item, target);
+
+        if self.root != Some(x) {
+            self.detach(x);
+            self.root = self.link(self.root, Some(x));
+        }
+        old
+    }
+
+    // remove the element handle refers to, or return None if it has been removed already
+    pub fn delete(&mut self, handle: Handle) -> Option<T> {
+        let x = self.nodes.index_of(handle)?;
+        if self.root == Some(x) {
+            return self.remove();
+        }
+
+        // the children of x form a heap of their own, which is melded back with the rest
+        self.detach(x);
+        let child = self.nodes[x].child.take();
+        let subtree = self.combine(child);
+        self.root = self.link(self.root, subtree);
+        self.len -= 1;
+        Some(self.nodes.remove(x).item)
+    }
+
+    // move all elements of other into self in O(1).
+    // handles returned by other are not valid for self, the returned function maps them
+    // to handles of the same elements in self. handles returned by self stay valid.
+    pub fn meld(&mut self, other: PairingHeap<T, O>) -> impl Fn(Handle) -> Handle {
+        let offset = self.nodes.absorb(other.nodes, |node, offset| {
+            node.child = node.child.map(|c| c + offset);
+            node.next = node.next.map(|n| n + offset);
+            node.prev = node.prev.map(|p| p + offset);
+        });
+
+        self.root = self.link(self.root, other.root.map(|r| r + offset));
+        self.len += other.len;
+
+        move |handle| Handle {
+            index: handle.index + offset,
+            generation: handle.generation,
+        }
+    }
+}
+
+// private methods
+impl<T, O: Order<T>> PairingHeap<T, O> {
+    // meld two trees, both roots have no siblings
+    fn link(&mut self, a: Option<usize>, b: Option<usize>) -> Option<usize> {
+        let (mut a, mut b) = match (a, b) {
+            (None, x) | (x, None) => return x,
+            (Some(a), Some(b)) => (a, b),
+        };
+        if self.order.precedes(&self.nodes[b].item, &self.nodes[a].item) {
+            mem::swap(&mut a, &mut b);
+        }
+
+        // b becomes the leftmost child of a
+        let child = self.nodes[a].child;
+        if let Some(c) = child {
+            self.nodes[c].prev = Some(b);
+        }
+        self.nodes[b].next = child;
+        self.nodes[b].prev = Some(a);
+        self.nodes[a].child = Some(b);
+        Some(a)
+    }
+
+    // cut the subtree rooted at x from its parent and siblings
+    fn detach(&mut self, x: usize) {
+        let prev = self.nodes[x].prev.take().unwrap();
+        let next = self.nodes[x].next.take();
+        if self.nodes[prev].child == Some(x) {
+            self.nodes[prev].child = next;
+        } else {
+            self.nodes[prev].next = next;
+        }
+        if let Some(n) = next {
+            self.nodes[n].prev = Some(prev);
+        }
+    }
+
+    // two pass pairing of the sibling list starting at first, returns the new root
+    fn combine(&mut self, first: Option<usize>) -> Option<usize> {
+        let mut trees = Vec::new();
+        let mut x = first;
+        while let Some(i) = x {
+            x = self.nodes[i].next.take();
+            self.nodes[i].prev = None;
+            trees.push(i);
+        }
+
+        // left to right in pairs
+        let pairs: Vec<_> = trees.chunks(2)
+            .map(|pair| self.link(Some(pair[0]), pair.get(1).copied()))
+            .collect();
+
+        // right to left into a single tree
+        pairs.into_iter().rev().fold(None, |root, tree| self.link(tree, root))
+    }
+}
+
+impl<T, O: Order<T> + Default> Default for PairingHeap<T, O> {
+    fn default() -> PairingHeap<T, O> {
+        PairingHeap::with_order(O::default())
+    }
+}
+
+impl<T, O: Order<T>> PriorityQueue<T> for PairingHeap<T, O> {
+    fn insert(&mut self, target: T) {
+        PairingHeap::insert(self, target);
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        PairingHeap::remove(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        PairingHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        PairingHeap::len(self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut h = PairingHeap::new();
+        for &x in [3,7,6,2,1,5,4,8].iter() {
+            h.insert(x);
+        }
+        assert_eq!(h.len(), 8);
+        assert_eq!(h.peek(), Some(&8));
+
+        for i in (1..=8).rev() {
+            assert_eq!(h.remove(), Some(i));
+        }
+        assert_eq!(h.remove(), None);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_decrease_key_and_delete() {
+        let mut h: PairingHeap<i32, Min> = PairingHeap::new_min();
+        let handles: Vec<_> = (0..10).map(|i| h.insert(10 * i)).collect();
+        assert_eq!(h.remove(), Some(0));
+
+        assert_eq!(h.decrease_key(handles[7], 5), 70);
+        assert_eq!(h.peek(), Some(&5));
+        assert_eq!(h.delete(handles[3]), Some(30));
+        assert_eq!(h.delete(handles[3]), None);
+        assert_eq!(h.get(handles[0]), None);
+        assert_eq!(h.get(handles[9]), Some(&90));
+
+        let removed: Vec<_> = std::iter::from_fn(|| h.remove()).collect();
+        assert_eq!(removed, vec![5, 10, 20, 40, 50, 60, 80, 90]);
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a: PairingHeap<i32, Min> = PairingHeap::new_min();
+        let mut b: PairingHeap<i32, Min> = PairingHeap::new_min();
+        let ha: Vec<_> = (0..20).map(|i| a.insert(2 * i)).collect();
+        let hb: Vec<_> = (0..20).map(|i| b.insert(2 * i + 1)).collect();
+
+        let remap = a.meld(b);
+        assert_eq!(a.len(), 40);
+        assert_eq!(a.delete(ha[0]), Some(0));
+        assert_eq!(a.delete(remap(hb[0])), Some(1));
+        a.decrease_key(remap(hb[19]), -1);
+
+        assert_eq!(a.remove(), Some(-1));
+        assert_eq!(a.remove(), Some(2));
+        assert_eq!(a.remove(), Some(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "not on the heap")]
+    fn test_decrease_removed_key() {
+        let mut h: PairingHeap<i32, Min> = PairingHeap::new_min();
+        let handle = h.insert(3);
+        h.remove();
+        h.decrease_key(handle, 1);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut gen = rand::thread_rng();
+        let mut h: PairingHeap<u32, Min> = PairingHeap::new_min();
+        let mut live: Vec<(Handle, u32)> = Vec::new();
+
+        for _ in 0..5000 {
+            match gen.gen_range(0, 4) {
+                0 | 1 => {
+                    let key = gen.gen_range(0, 1000);
+                    live.push((h.insert(key), key));
+                },
+                2 if !live.is_empty() => {
+                    let i = gen.gen_range(0, live.len());
+                    let key = live[i].1 / 2;
+                    h.decrease_key(live[i].0, key);
+                    live[i].1 = key;
+                },
+                _ if !live.is_empty() => {
+                    if gen.gen() {
+                        let i = gen.gen_range(0, live.len());
+                        let (handle, key) = live.swap_remove(i);
+                        assert_eq!(h.delete(handle), Some(key));
+                    } else {
+                        let top = h.remove().unwrap();
+                        assert_eq!(live.iter().map(|&(_, key)| key).min(), Some(top));
+                        let i = live.iter().position(|&(handle, _)| !h.contains(handle)).unwrap();
+                        assert_eq!(live.swap_remove(i).1, top);
+                    }
+                },
+                _ => assert_eq!(h.remove(), None),
+            }
+            assert_eq!(h.len(), live.len());
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::rc::Rc;
+
+use super::order::{Max, Min, Order};
+
+// a persistent leftist heap: operations return a new version of the heap and leave the old
+// one untouched, e.g. to keep a queue per node of a search tree.
+// versions share structure, a merge only copies the O(logn) nodes on the right spines it
+// walks(see leftist_heap), so insert, remove and meld take O(logn) time and space and
+// cloning a heap takes O(1).
+//
+// reference: Okasaki, Purely Functional Data Structures, 3.1
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+struct Node<T> {
+    item: T,
+    rank: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+fn rank<T>(link: &Link<T>) -> usize {
+    match link {
+        Some(node) => node.rank,
+        None => 0,
+    }
+}
+
+// a node with the given children, which are swapped if needed to keep the leftist property
+fn make_node<T>(item: T, a: Link<T>, b: Link<T>) -> Link<T> {
+    let (left, right) = if rank(&a) >= rank(&b) { (a, b) } else { (b, a) };
+    Some(Rc::new(Node {
+        item,
+        rank: rank(&right) + 1,
+        left,
+        right,
+    }))
+}
+
+fn merge<T: Clone, O: Order<T>>(a: &Link<T>, b: &Link<T>, order: &O) -> Link<T> {
+    match (a, b) {
+        (None, link) | (link, None) => link.clone(),
+        (Some(x), Some(y)) => {
+            let (top, other) = if order.precedes(&y.item, &x.item) { (y, x) } else { (x, y) };
+            let right = merge(&top.right, &Some(Rc::clone(other)), order);
+            make_node(top.item.clone(), top.left.clone(), right)
+        },
+    }
+}
+
+pub struct PersistentHeap<T, O = Max> {
+    root: Link<T>,
+    len: usize,
+    order: O,
+}
+
+impl<T: PartialOrd + Clone> PersistentHeap<T, Max> {
+    pub fn new() -> PersistentHeap<T> {
+        PersistentHeap::with_order(Max)
+    }
+}
+
+impl<T: PartialOrd + Clone> PersistentHeap<T, Min> {
+    pub fn new_min() -> PersistentHeap<T, Min> {
+        PersistentHeap::with_order(Min)
+    }
+}
+
+impl<T: Clone, O: Order<T> + Clone> PersistentHeap<T, O> {
+    pub fn with_order(order: O) -> PersistentHeap<T, O> {
+        PersistentHeap {
+            root: None,
+            len: 0,
+            order,
+        }
+    }
+
+    // a new version with target added
+    pub fn insert(&self, target: T) -> PersistentHeap<T, O> {
+        let node = make_node(target, None, None);
+        self.with_root(merge(&self.root, &node, &self.order), self.len + 1)
+    }
+
+    // the top element and a new version without it
+    pub fn remove(&self) -> Option<(&T, PersistentHeap<T, O>)> {
+        let node = self.root.as_ref()?;
+        let rest = self.with_root(merge(&node.left, &node.right, &self.order), self.len - 1);
+        Some((&node.item, rest))
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.item)
+    }
+
+    // a new version with the elements of both self and other
+    pub fn meld(&self, other: &PersistentHeap<T, O>) -> PersistentHeap<T, O> {
+        self.with_root(merge(&self.root, &other.root, &self.order), self.len + other.len)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn with_root(&self, root: Link<T>, len: usize) -> PersistentHeap<T, O> {
+        PersistentHeap {
+            root,
+            len,
+            order: self.order.clone(),
+        }
+    }
+}
+
+// O(1), the versions share all nodes
+impl<T, O: Clone> Clone for PersistentHeap<T, O> {
+    fn clone(&self) -> PersistentHeap<T, O> {
+        PersistentHeap {
+            root: self.root.clone(),
+            len: self.len,
+            order: self.order.clone(),
+        }
+    }
+}
+
+impl<T: Clone, O: Order<T> + Clone + Default> Default for PersistentHeap<T, O> {
+    fn default() -> PersistentHeap<T, O> {
+        PersistentHeap::with_order(O::default())
+    }
+}
+
+// the left spine can be O(n) long, so release the nodes no other version refers to
+// iteratively to not overflow the stack
+impl<T, O> Drop for PersistentHeap<T, O> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Rc<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(rc) = stack.pop() {
+            if let Ok(mut node) = Rc::try_unwrap(rc) {
+                stack.extend(node.left.take());
+                stack.extend(node.right.take());
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    fn to_vec<T: Clone, O: Order<T> + Clone>(h: &PersistentHeap<T, O>) -> Vec<T> {
+        let mut res = Vec::new();
+        let mut h = h.clone();
+        while let Some((top, rest)) = h.remove().map(|(top, rest)| (top.clone(), rest)) {
+            res.push(top);
+            h = rest;
+        }
+        res
+    }
+
+    #[test]
+    fn test_versions() {
+        let empty = PersistentHeap::new();
+        let a = empty.insert(3).insert(1).insert(4);
+        let b = a.insert(5);
+        let c = a.insert(0);
+
+        assert!(empty.is_empty());
+        assert_eq!(to_vec(&a), vec![4, 3, 1]);
+        assert_eq!(to_vec(&b), vec![5, 4, 3, 1]);
+        assert_eq!(to_vec(&c), vec![4, 3, 1, 0]);
+
+        let (top, rest) = b.remove().unwrap();
+        assert_eq!(*top, 5);
+        assert_eq!(rest.len(), 3);
+        assert_eq!(b.peek(), Some(&5));
+        assert_eq!(to_vec(&rest.meld(&c)), vec![4, 4, 3, 3, 1, 1, 0]);
+        assert_eq!(a.len(), 3);
+    }
+
+    #[test]
+    fn test_branching() {
+        // every version derived from a random earlier one, checked against sorted vectors
+        let mut gen = rand::thread_rng();
+        let mut versions = vec![(PersistentHeap::new_min(), Vec::new())];
+
+        for _ in 0..500 {
+            let (h, v) = versions[gen.gen_range(0, versions.len())].clone();
+            let next = if v.is_empty() || gen.gen_range(0, 3) > 0 {
+                let x: u32 = gen.gen_range(0, 100);
+                let mut v = v;
+                v.push(x);
+                v.sort();
+                (h.insert(x), v)
+            } else {
+                let (top, rest) = h.remove().unwrap();
+                assert_eq!(*top, v[0]);
+                (rest, v[1..].to_vec())
+            };
+            versions.push(next);
+        }
+
+        for (h, v) in versions.iter() {
+            assert_eq!(h.len(), v.len());
+            assert_eq!(&to_vec(h), v);
+        }
+    }
+
+    #[test]
+    fn test_long_spine() {
+        // decreasing keys build a left spine of length n in a min heap
+        let mut h = PersistentHeap::new_min();
+        for i in (0..100_000).rev() {
+            h = h.insert(i);
+        }
+        let old = h.clone();
+        let (_, h) = h.remove().unwrap();
+        assert_eq!(h.peek(), Some(&1));
+        assert_eq!(old.peek(), Some(&0));
+    }
+}
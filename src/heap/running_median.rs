@@ -0,0 +1,430 @@
+use std::collections::VecDeque;
+
+use super::binary_heap::Heap;
+use super::order::{Max, Min, Order};
+
+// the quantile of a stream kept with two heaps: a max heap with the smaller elements and a
+// min heap with the larger ones, sized so that the quantile is on top of the max heap.
+// insert and remove rebalance the heaps by moving tops across, in O(logn).
+//
+// heaps cannot remove arbitrary elements, so removal is lazy: a removed element is pushed
+// onto a deletion heap next to the heap that holds it, and whenever both tops are equal
+// they are removed together. the tops of the two main heaps are therefore always live.
+// deleted elements that never reach the top, e.g. the oldest ones of a monotone stream,
+// would pile up, so a heap is rebuilt without them once they outnumber the live ones:
+// this keeps both heaps O(n) and costs amortized O(logn) per deletion.
+//
+// elements must be totally ordered among themselves, e.g. floats must not be NaN.
+
+// a heap with lazily deleted elements
+struct LazyHeap<T, O> {
+    heap: Heap<T, O>,
+    deleted: Heap<T, O>,
+    // live elements
+    len: usize,
+}
+
+impl<T: PartialOrd, O: Order<T> + Default> LazyHeap<T, O> {
+    fn new() -> LazyHeap<T, O> {
+        LazyHeap {
+            heap: Heap::default(),
+            deleted: Heap::default(),
+            len: 0,
+        }
+    }
+
+    fn insert(&mut self, target: T) {
+        self.heap.insert(target);
+        self.len += 1;
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        let res = self.heap.remove()?;
+        self.len -= 1;
+        self.prune();
+        Some(res)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    // returns false if target cannot be on the heap: the heap is empty or target comes
+    // before the top, which is live
+    fn delete(&mut self, target: T) -> bool {
+        match self.heap.peek() {
+            Some(top) if !self.heap.order().precedes(&target, top) => (),
+            _ => return false,
+        }
+
+        self.deleted.insert(target);
+        self.len -= 1;
+        self.prune();
+        if self.deleted.len() > self.len {
+            self.rebuild();
+        }
+        true
+    }
+
+    // drop deleted elements from the top
+    fn prune(&mut self) {
+        while let (Some(a), Some(b)) = (self.heap.peek(), self.deleted.peek()) {
+            if a != b {
+                break;
+            }
+            self.heap.remove();
+            self.deleted.remove();
+        }
+
+        // every deleted element is on heap, after the live top
+        if let Some(d) = self.deleted.peek() {
+            debug_assert!(matches!(self.heap.peek(), Some(top) if !self.heap.order().precedes(d, top)),
+                "removed an element that was never inserted");
+        }
+    }
+
+    // drop all deleted elements: both heaps yield their elements in the same order, and
+    // every deleted element is on heap, so they are matched like in a merge
+    fn rebuild(&mut self) {
+        let mut live = Vec::with_capacity(self.len);
+        while let Some(x) = self.heap.remove() {
+            if self.deleted.peek() == Some(&x) {
+                self.deleted.remove();
+            } else {
+                live.push(x);
+            }
+        }
+        debug_assert!(self.deleted.is_empty(), "removed an element that was never inserted");
+        // in heap order already, so this does not move anything
+        self.heap = Heap::from_with_order(live, O::default());
+    }
+}
+
+// the q-quantile of a multiset of elements, i.e. the element with rank floor(q*(n-1))
+// (0 based) among the n elements in ascending order: q = 0 is the minimum, q = 1 the maximum.
+pub struct RunningQuantile<T> {
+    q: f64,
+    // the floor(q*(n-1))+1 smallest elements, the quantile on top
+    low: LazyHeap<T, Max>,
+    high: LazyHeap<T, Min>,
+}
+
+impl<T: PartialOrd> RunningQuantile<T> {
+    // panics if q is not in [0, 1]
+    pub fn new(q: f64) -> RunningQuantile<T> {
+        assert!((0.0..=1.0).contains(&q), "quantile {} is not in [0, 1]", q);
+        RunningQuantile {
+            q,
+            low: LazyHeap::new(),
+            high: LazyHeap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, target: T) {
+        match self.low.peek() {
+            Some(top) if target > *top => self.high.insert(target),
+            _ => self.low.insert(target),
+        }
+        self.rebalance();
+    }
+
+    // remove one element equal to target, returns false and leaves the elements unchanged
+    // if target is certainly not present: there are no elements, or target falls between
+    // the quantile and the next larger element.
+    // other elements that were never inserted cannot be detected without a full search:
+    // removing one panics in debug builds and leaves the result unspecified otherwise.
+    pub fn remove(&mut self, target: T) -> bool {
+        let removed = match self.low.peek() {
+            Some(top) if target > *top => self.high.delete(target),
+            _ => self.low.delete(target),
+        };
+        if removed {
+            self.rebalance();
+        }
+        removed
+    }
+
+    // the current quantile, None if there are no elements
+    pub fn quantile(&self) -> Option<&T> {
+        self.low.peek()
+    }
+
+    pub fn len(&self) -> usize {
+        self.low.len + self.high.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // move tops across until low holds exactly the elements up to the quantile
+    fn rebalance(&mut self) {
+        let n = self.len();
+        let target = if n == 0 { 0 } else { (self.q * (n-1) as f64).floor() as usize + 1 };
+        while self.low.len > target {
+            let x = self.low.remove().unwrap();
+            self.high.insert(x);
+        }
+        while self.low.len < target {
+            let x = self.high.remove().unwrap();
+            self.low.insert(x);
+        }
+    }
+}
+
+// the median of a multiset of elements.
+// for an even number of elements there are two medians, the lower and the upper one.
+pub struct RunningMedian<T> {
+    inner: RunningQuantile<T>,
+}
+
+impl<T: PartialOrd> RunningMedian<T> {
+    pub fn new() -> RunningMedian<T> {
+        RunningMedian { inner: RunningQuantile::new(0.5) }
+    }
+
+    pub fn insert(&mut self, target: T) {
+        self.inner.insert(target);
+    }
+
+    // remove one element equal to target, see RunningQuantile::remove
+    pub fn remove(&mut self, target: T) -> bool {
+        self.inner.remove(target)
+    }
+
+    pub fn lower_median(&self) -> Option<&T> {
+        self.inner.quantile()
+    }
+
+    pub fn upper_median(&self) -> Option<&T> {
+        // for an even number of elements both heaps hold half of them
+        if self.inner.low.len == self.inner.high.len {
+            self.inner.high.peek()
+        } else {
+            self.inner.low.peek()
+        }
+    }
+
+    // the mean of the lower and the upper median
+    pub fn median(&self) -> Option<f64>
+    where
+        T: Copy + Into<f64>,
+    {
+        let lower: f64 = (*self.lower_median()?).into();
+        let upper: f64 = (*self.upper_median()?).into();
+        Some((lower + upper) / 2.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<T: PartialOrd> Default for RunningMedian<T> {
+    fn default() -> RunningMedian<T> {
+        RunningMedian::new()
+    }
+}
+
+// the median of the last window elements of a stream
+pub struct SlidingMedian<T> {
+    window: VecDeque<T>,
+    size: usize,
+    median: RunningMedian<T>,
+}
+
+impl<T: PartialOrd + Clone> SlidingMedian<T> {
+    // panics if size is 0
+    pub fn new(size: usize) -> SlidingMedian<T> {
+        assert!(size > 0, "the window must hold at least one element");
+        SlidingMedian {
+            window: VecDeque::with_capacity(size + 1),
+            size,
+            median: RunningMedian::new(),
+        }
+    }
+
+    // add target to the window, evicting the oldest element once the window is full
+    pub fn push(&mut self, target: T) {
+        self.window.push_back(target.clone());
+        self.median.insert(target);
+        if self.window.len() > self.size {
+            let oldest = self.window.pop_front().unwrap();
+            let removed = self.median.remove(oldest);
+            debug_assert!(removed);
+        }
+    }
+
+    pub fn lower_median(&self) -> Option<&T> {
+        self.median.lower_median()
+    }
+
+    pub fn upper_median(&self) -> Option<&T> {
+        self.median.upper_median()
+    }
+
+    pub fn median(&self) -> Option<f64>
+    where
+        T: Copy + Into<f64>,
+    {
+        self.median.median()
+    }
+
+    // number of elements in the window, at most its size
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    fn rank(sorted: &[u32], q: f64) -> Option<&u32> {
+        if sorted.is_empty() {
+            None
+        } else {
+            sorted.get((q * (sorted.len()-1) as f64).floor() as usize)
+        }
+    }
+
+    #[test]
+    fn test_median() {
+        let mut m = RunningMedian::new();
+        assert_eq!(m.lower_median(), None);
+        assert_eq!(m.median(), None);
+
+        m.insert(5);
+        assert_eq!(m.median(), Some(5.0));
+        m.insert(1);
+        assert_eq!((m.lower_median(), m.upper_median()), (Some(&1), Some(&5)));
+        assert_eq!(m.median(), Some(3.0));
+        m.insert(3);
+        assert_eq!((m.lower_median(), m.upper_median()), (Some(&3), Some(&3)));
+
+        assert!(m.remove(3));
+        assert!(m.remove(5));
+        assert_eq!(m.median(), Some(1.0));
+        assert!(m.remove(1));
+        assert!(m.is_empty());
+        assert!(!m.remove(1));
+    }
+
+    #[test]
+    fn test_remove_absent() {
+        let mut q = RunningQuantile::new(0.5);
+        for &x in [10, 20, 30, 40].iter() {
+            q.insert(x);
+        }
+
+        // between the quantile and the next larger element
+        assert!(!q.remove(25));
+        assert!(!q.remove(21));
+        assert!(!RunningQuantile::new(0.5).remove(1));
+        assert_eq!(q.len(), 4);
+        assert_eq!(q.quantile(), Some(&20));
+
+        assert!(q.remove(30));
+        assert_eq!(q.quantile(), Some(&20));
+        assert_eq!(q.len(), 3);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "never inserted")]
+    fn test_remove_never_inserted() {
+        // 15 goes to the heap of the smaller elements, where it is not found
+        let mut q = RunningQuantile::new(0.5);
+        for &x in [10, 20, 30, 40].iter() {
+            q.insert(x);
+        }
+        q.remove(15);
+        q.remove(20);
+        q.remove(10);
+    }
+
+    #[test]
+    fn test_quantiles() {
+        let mut gen = rand::thread_rng();
+        for &q in [0.0, 0.1, 0.5, 0.9, 0.99, 1.0].iter() {
+            let mut rq = RunningQuantile::new(q);
+            let mut sorted: Vec<u32> = Vec::new();
+
+            for _ in 0..2000 {
+                if sorted.is_empty() || gen.gen_range(0, 3) > 0 {
+                    let x = gen.gen_range(0, 100);
+                    rq.insert(x);
+                    let i = sorted.iter().position(|&y| y > x).unwrap_or(sorted.len());
+                    sorted.insert(i, x);
+                } else {
+                    let x = sorted.remove(gen.gen_range(0, sorted.len()));
+                    assert!(rq.remove(x));
+                }
+                assert_eq!(rq.len(), sorted.len());
+                assert_eq!(rq.quantile(), rank(&sorted, q));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sliding_median() {
+        let mut gen = rand::thread_rng();
+        let v: Vec<u32> = (0..1000).map(|_| gen.gen_range(0, 50)).collect();
+        let size = 7;
+
+        let mut m = SlidingMedian::new(size);
+        for (i, &x) in v.iter().enumerate() {
+            m.push(x);
+            let mut window = v[(i+1).saturating_sub(size)..=i].to_vec();
+            window.sort();
+            assert_eq!(m.len(), window.len());
+            assert_eq!(m.lower_median(), window.get((window.len()-1)/2));
+            assert_eq!(m.upper_median(), window.get(window.len()/2));
+        }
+    }
+
+    #[test]
+    fn test_monotone_stream() {
+        // the evicted elements are never on top, the heaps must not grow with the stream
+        let size = 3;
+        let mut m = SlidingMedian::new(size);
+        let stream = (0..100_000).rev().chain(0..100_000);
+        for (i, x) in stream.enumerate() {
+            m.push(x);
+            let q = &m.median.inner;
+            assert!(q.low.heap.len() + q.high.heap.len() <= 2 * size + 2, "heaps grew at {}", i);
+            assert!(q.low.deleted.len() + q.high.deleted.len() <= size + 2, "deleted grew at {}", i);
+        }
+        assert_eq!(m.lower_median(), Some(&99_998));
+    }
+
+    #[test]
+    fn test_float_latencies() {
+        let mut m = SlidingMedian::new(4);
+        for &x in [12.5, 3.0, 8.0, 100.0, 7.0].iter() {
+            m.push(x);
+        }
+        // window: 3.0, 8.0, 100.0, 7.0
+        assert_eq!(m.median(), Some(7.5));
+    }
+
+    #[test]
+    #[should_panic(expected = "not in [0, 1]")]
+    fn test_invalid_quantile() {
+        let _: RunningQuantile<u32> = RunningQuantile::new(1.5);
+    }
+}
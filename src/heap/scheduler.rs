@@ -0,0 +1,254 @@
+use std::collections::HashSet;
+
+use super::binary_heap::Heap;
+use super::order::Order;
+
+// a discrete event scheduler for simulations: events are scheduled at virtual timestamps
+// and processed in time order, advancing the virtual clock to the time of each event.
+//
+// events with the same timestamp are processed in the order they were scheduled, so a
+// simulation that schedules the same events produces the same run every time.
+// cancelled events stay on the heap and are skipped when they reach the top.
+
+// identifies a scheduled event, e.g. to cancel it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventId(u64);
+
+struct Entry<E> {
+    at: u64,
+    // the scheduling sequence number, which breaks ties between equal timestamps
+    id: EventId,
+    event: E,
+}
+
+// the earliest entry on top
+struct Earliest;
+
+impl<E> Order<Entry<E>> for Earliest {
+    fn precedes(&self, a: &Entry<E>, b: &Entry<E>) -> bool {
+        (a.at, a.id.0) < (b.at, b.id.0)
+    }
+}
+
+pub struct EventScheduler<E> {
+    queue: Heap<Entry<E>, Earliest>,
+    // events scheduled and neither processed nor cancelled
+    pending: HashSet<EventId>,
+    now: u64,
+    next_id: u64,
+}
+
+impl<E> EventScheduler<E> {
+    pub fn new() -> EventScheduler<E> {
+        EventScheduler {
+            queue: Heap::with_order(Earliest),
+            pending: HashSet::new(),
+            now: 0,
+            next_id: 0,
+        }
+    }
+
+    // the virtual time: the time of the last processed event, or the end of the last run
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    // panics if at is before now
+    pub fn schedule(&mut self, at: u64, event: E) -> EventId {
+        assert!(at >= self.now, "cannot schedule an event at {} before the current time {}", at, self.now);
+
+        let id = EventId(self.next_id);
+        self.next_id += 1;
+        self.queue.insert(Entry { at, id, event });
+        self.pending.insert(id);
+        id
+    }
+
+    // panics if now + delay overflows
+    pub fn schedule_after(&mut self, delay: u64, event: E) -> EventId {
+        let at = self.now.checked_add(delay).expect("scheduling an event after the delay overflows the clock");
+        self.schedule(at, event)
+    }
+
+    // returns false if the event was processed or cancelled already
+    pub fn cancel(&mut self, id: EventId) -> bool {
+        let cancelled = self.pending.remove(&id);
+        self.skip_cancelled();
+        cancelled
+    }
+
+    pub fn is_pending(&self, id: EventId) -> bool {
+        self.pending.contains(&id)
+    }
+
+    // the time of the next event
+    pub fn next_time(&self) -> Option<u64> {
+        self.queue.peek().map(|entry| entry.at)
+    }
+
+    // remove the next event if it is due at or before until, advancing the clock to it
+    pub fn pop_due(&mut self, until: u64) -> Option<(u64, E)> {
+        if self.next_time()? > until {
+            return None;
+        }
+
+        let entry = self.queue.remove().unwrap();
+        self.pending.remove(&entry.id);
+        self.skip_cancelled();
+        self.now = entry.at;
+        Some((entry.at, entry.event))
+    }
+
+    // process all events due at or before until, in order, then advance the clock to until.
+    // the handler may schedule and cancel events, events it schedules up to until are
+    // processed in the same run. returns the number of processed events.
+    pub fn run<F>(&mut self, until: u64, mut handler: F) -> usize
+    where
+        F: FnMut(&mut EventScheduler<E>, u64, E),
+    {
+        let mut processed = 0;
+        while let Some((at, event)) = self.pop_due(until) {
+            handler(self, at, event);
+            processed += 1;
+        }
+        self.now = self.now.max(until);
+        processed
+    }
+
+    // the number of pending events
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    // drop cancelled events from the top, so that the top is always pending
+    fn skip_cancelled(&mut self) {
+        while let Some(entry) = self.queue.peek() {
+            if self.pending.contains(&entry.id) {
+                break;
+            }
+            self.queue.remove();
+        }
+    }
+}
+
+impl<E> Default for EventScheduler<E> {
+    fn default() -> EventScheduler<E> {
+        EventScheduler::new()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_order() {
+        let mut s = EventScheduler::new();
+        s.schedule(30, "c");
+        s.schedule(10, "a");
+        s.schedule(20, "b1");
+        s.schedule(20, "b2");
+        s.schedule(20, "b3");
+        assert_eq!(s.len(), 5);
+        assert_eq!(s.next_time(), Some(10));
+
+        assert_eq!(s.pop_due(5), None);
+        assert_eq!(s.pop_due(10), Some((10, "a")));
+        assert_eq!(s.now(), 10);
+
+        // ties in scheduling order
+        let due: Vec<_> = std::iter::from_fn(|| s.pop_due(25)).collect();
+        assert_eq!(due, vec![(20, "b1"), (20, "b2"), (20, "b3")]);
+        assert_eq!(s.now(), 20);
+        assert_eq!(s.len(), 1);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut s = EventScheduler::new();
+        let a = s.schedule(1, 'a');
+        let b = s.schedule(2, 'b');
+        let c = s.schedule(3, 'c');
+
+        assert!(s.cancel(a));
+        assert!(!s.cancel(a));
+        assert!(!s.is_pending(a));
+        assert_eq!(s.next_time(), Some(2));
+        assert_eq!(s.len(), 2);
+
+        assert_eq!(s.pop_due(u64::MAX), Some((2, 'b')));
+        assert!(!s.cancel(b));
+        assert!(s.cancel(c));
+        assert!(s.is_empty());
+        assert_eq!(s.pop_due(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_run() {
+        // two hosts sending a packet back and forth with a latency of 7, until one is lost
+        #[derive(Debug)]
+        enum Event {
+            Arrive { host: usize, hop: u32 },
+            Lost,
+        }
+
+        let mut s = EventScheduler::new();
+        s.schedule(0, Event::Arrive { host: 0, hop: 0 });
+        let lost = s.schedule(100, Event::Lost);
+
+        let mut log = Vec::new();
+        let processed = s.run(50, |s, at, event| {
+            match event {
+                Event::Arrive { host, hop } => {
+                    log.push((at, host));
+                    s.schedule_after(7, Event::Arrive { host: 1 - host, hop: hop + 1 });
+                },
+                Event::Lost => panic!("not due before 100"),
+            }
+        });
+
+        assert_eq!(processed, 8);
+        assert_eq!(log.last(), Some(&(49, 1)));
+        assert_eq!(s.now(), 50);
+        assert_eq!(s.next_time(), Some(56));
+        assert!(s.is_pending(lost));
+
+        let processed = s.run(200, |s, _, event| {
+            if let Event::Lost = event {
+                // drop everything in flight
+                while s.pop_due(u64::MAX).is_some() {}
+            } else {
+                s.schedule_after(7, event);
+            }
+        });
+        assert_eq!(processed, 8);
+        assert!(s.is_empty());
+        assert_eq!(s.now(), 200);
+    }
+
+    #[test]
+    #[should_panic(expected = "before the current time")]
+    fn test_schedule_in_the_past() {
+        let mut s = EventScheduler::new();
+        s.schedule(10, ());
+        s.pop_due(10);
+        s.schedule(9, ());
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows the clock")]
+    fn test_schedule_after_overflow() {
+        let mut s = EventScheduler::new();
+        s.schedule(10, ());
+        s.pop_due(10);
+        s.schedule_after(u64::MAX, ());
+    }
+}
@@ -0,0 +1,77 @@
+// sink and swim for heaps stored in level order, shared by all array based heaps.
+// implementors only describe how to compare and exchange the elements at two positions,
+// e.g. Heap compares its elements directly while IndexPQ compares the keys its positions refer to.
+//
+// for a tree with arity d:
+// parent of node at index k: (k-1)/d
+// children of node at index k: d*k+1, ..., d*k+d
+pub(crate) trait Sift {
+    const ARITY: usize = 2;
+
+    fn heap_len(&self) -> usize;
+
+    // the element at position i should be placed above the element at position j
+    fn precedes(&self, i: usize, j: usize) -> bool;
+
+    fn swap(&mut self, i: usize, j: usize);
+
+    // sink implements top-down heapify
+    // sink is used when heap invariant is violated because a node becomes
+    // smaller than one or more of its children(for max heap).
+    fn sink(&mut self, index: usize) {
+        let end = self.heap_len();
+        self.sink_to(index, end);
+    }
+
+    // sink within positions [0..end), ignoring the elements after it
+    fn sink_to(&mut self, index: usize, end: usize) {
+        let mut i = index;
+        loop {
+            // stop when v[i] is a leave
+            let first = Self::ARITY*i+1;
+            if first >= end {
+                break;
+            }
+
+            // pick the child that should be on top
+            let mut j = first;
+            for child in first+1..end.min(first+Self::ARITY) {
+                if self.precedes(child, j) {
+                    j = child;
+                }
+            }
+
+            if !self.precedes(j, i) {
+                break;
+            }
+
+            self.swap(i, j);
+            i = j;
+        }
+    }
+
+    // swim implements bottom-up heapify.
+    // swim is used when heap invariant is violated because a node becomes:
+    //     larger than its parent(for max heap);
+    //     smaller than its parent(for min heap).
+    fn swim(&mut self, index: usize) {
+        let mut i = index;
+        while i > 0 && self.precedes(i, (i-1)/Self::ARITY) {
+            self.swap(i, (i-1)/Self::ARITY);
+            i = (i-1)/Self::ARITY;
+        }
+    }
+
+    // fix the heap to retain the heap invariant, in O(n)
+    fn fix(&mut self) {
+        let len = self.heap_len();
+        if len <= 1 {
+            return;
+        }
+
+        // the last internal node is the parent of the last element
+        for i in (0..=(len-2)/Self::ARITY).rev() {
+            self.sink(i);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::mem::swap;
+
+use super::order::{Max, Min, Order};
+use super::PriorityQueue;
+
+// a skew heap is the self-adjusting version of the leftist heap: it keeps no rank,
+// and unconditionally swaps the children of every node on the merge path.
+// single operations can take O(n), but meld, insert and remove run in amortized O(logn).
+//
+// references:
+// https://en.wikipedia.org/wiki/Skew_heap
+// Sleator and Tarjan, Self-Adjusting Heaps (1986)
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    item: T,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+// top-down merge: walk down the right spines of a and b, always taking the node that
+// precedes, and hang the rest of the merge on its left after moving its left child to
+// the right. the merge path can be O(n) long, so it is iterative.
+fn merge<T, O: Order<T>>(a: Link<T>, b: Link<T>, order: &O) -> Link<T> {
+    let mut root = None;
+    let mut tail = &mut root;
+    let (mut a, mut b) = (a, b);
+
+    loop {
+        match (a, b) {
+            (None, link) | (link, None) => {
+                *tail = link;
+                return root;
+            },
+            (Some(x), Some(y)) => {
+                let (mut top, other) = if order.precedes(&y.item, &x.item) { (y, x) } else { (x, y) };
+
+                a = top.right.take();
+                b = Some(other);
+                swap(&mut top.left, &mut top.right);
+
+                *tail = Some(top);
+                tail = &mut tail.as_mut().unwrap().left;
+            },
+        }
+    }
+}
+
+pub struct SkewHeap<T, O = Max> {
+    root: Link<T>,
+    len: usize,
+    order: O,
+}
+
+impl<T: PartialOrd> SkewHeap<T, Max> {
+    pub fn new() -> SkewHeap<T> {
+        SkewHeap::with_order(Max)
+    }
+}
+
+impl<T: PartialOrd> SkewHeap<T, Min> {
+    pub fn new_min() -> SkewHeap<T, Min> {
+        SkewHeap::with_order(Min)
+    }
+}
+
+impl<T, O: Order<T>> SkewHeap<T, O> {
+    pub fn with_order(order: O) -> SkewHeap<T, O> {
+        SkewHeap {
+            root: None,
+            len: 0,
+            order,
+        }
+    }
+
+    pub fn insert(&mut self, target: T) {
+        let node = Box::new(Node {
+            item: target,
+            left: None,
+            right: None,
+        });
+        self.root = merge(self.root.take(), Some(node), &self.order);
+        self.len += 1;
+    }
+
+    pub fn remove(&mut self) -> Option<T> {
+        let node = *self.root.take()?;
+        self.root = merge(node.left, node.right, &self.order);
+        self.len -= 1;
+        Some(node.item)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // move all elements of other into self in amortized O(logn)
+    pub fn meld(&mut self, mut other: SkewHeap<T, O>) {
+        self.root = merge(self.root.take(), other.root.take(), &self.order);
+        self.len += other.len;
+    }
+}
+
+impl<T, O: Order<T> + Default> Default for SkewHeap<T, O> {
+    fn default() -> SkewHeap<T, O> {
+        SkewHeap::with_order(O::default())
+    }
+}
+
+// the tree can be O(n) deep, drop the nodes iteratively to not overflow the stack
+impl<T, O> Drop for SkewHeap<T, O> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T, O: Order<T>> PriorityQueue<T> for SkewHeap<T, O> {
+    fn insert(&mut self, target: T) {
+        SkewHeap::insert(self, target);
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        SkewHeap::remove(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        SkewHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        SkewHeap::len(self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut h = SkewHeap::new();
+        for &x in [3,7,6,2,1,5,4,8].iter() {
+            h.insert(x);
+        }
+        assert_eq!(h.len(), 8);
+        assert_eq!(h.peek(), Some(&8));
+
+        for i in (1..=8).rev() {
+            assert_eq!(h.remove(), Some(i));
+        }
+        assert_eq!(h.remove(), None);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut gen = rand::thread_rng();
+        let mut a = SkewHeap::new_min();
+        let mut b = SkewHeap::new_min();
+        let mut expected = Vec::new();
+        for _ in 0..1000 {
+            let x = gen.gen_range(0, 100);
+            expected.push(x);
+            if gen.gen() {
+                a.insert(x);
+            } else {
+                b.insert(x);
+            }
+        }
+
+        a.meld(b);
+        expected.sort();
+        let removed: Vec<_> = std::iter::from_fn(|| a.remove()).collect();
+        assert_eq!(removed, expected);
+    }
+
+    #[test]
+    fn test_long_path() {
+        let mut h = SkewHeap::new();
+        for i in 0..100_000 {
+            h.insert(i);
+        }
+        assert_eq!(h.remove(), Some(99_999));
+        assert_eq!(h.len(), 99_999);
+    }
+}
@@ -0,0 +1,180 @@
+use std::slice;
+
+use super::binary_heap::Heap;
+use super::order::{Max, Min, Order, Reversed};
+
+// collects the k best elements of a stream, where the best element is the one an O heap
+// would have on top: the k largest for Max(the default), the k smallest for Min.
+//
+// the kept elements are stored in a heap with the reversed order, so the worst of them is
+// on top and can be compared with, and replaced by, a better incoming element in O(logk).
+// collecting n elements takes O(nlogk) time and O(k) space.
+pub struct TopK<T, O = Max> {
+    heap: Heap<T, Reversed<O>>,
+    k: usize,
+}
+
+impl<T: PartialOrd> TopK<T, Max> {
+    // keep the k largest elements
+    pub fn new(k: usize) -> TopK<T> {
+        TopK::with_order(k, Max)
+    }
+}
+
+impl<T: PartialOrd> TopK<T, Min> {
+    // keep the k smallest elements
+    pub fn new_min(k: usize) -> TopK<T, Min> {
+        TopK::with_order(k, Min)
+    }
+}
+
+impl<T, O: Order<T>> TopK<T, O> {
+    pub fn with_order(k: usize, order: O) -> TopK<T, O> {
+        TopK {
+            heap: Heap::with_order(Reversed(order)),
+            k,
+        }
+    }
+
+    // offer target to the collector, it is kept if it is among the k best so far.
+    // an element only as good as the worst kept one does not replace it.
+    pub fn push(&mut self, target: T) {
+        if self.heap.len() < self.k {
+            self.heap.insert(target);
+            return;
+        }
+
+        let better = match self.heap.peek() {
+            Some(worst) => self.heap.order().0.precedes(&target, worst),
+            None => false,
+        };
+        if better {
+            // the replaced top sinks when the PeekMut is dropped
+            *self.heap.peek_mut().unwrap() = target;
+        }
+    }
+
+    // the worst of the kept elements, which an element has to beat once the collector is full
+    pub fn worst(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    // the number of elements kept
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.heap.len() == self.k
+    }
+
+    // iterate over the kept elements in arbitrary order
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    // combine with a collector of another part of the stream, e.g. another shard.
+    // the result keeps the k best elements of both.
+    pub fn merge(&mut self, other: TopK<T, O>) {
+        for x in other.heap.into_vec() {
+            self.push(x);
+        }
+    }
+
+    // the kept elements, best first
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        // the reversed heap puts its top, the worst element, last
+        self.heap.into_sorted_vec()
+    }
+}
+
+impl<T, O: Order<T>> Extend<T> for TopK<T, O> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    use crate::heap::order::ByKey;
+
+    #[test]
+    fn test_top_k() {
+        let mut top = TopK::new(3);
+        top.extend(vec![5, 1, 9, 3, 7, 9, 2]);
+        assert!(top.is_full());
+        assert_eq!(top.worst(), Some(&7));
+        assert_eq!(top.into_sorted_vec(), vec![9, 9, 7]);
+
+        let mut bottom = TopK::new_min(3);
+        bottom.extend(vec![5, 1, 9, 3, 7, 9, 2]);
+        assert_eq!(bottom.into_sorted_vec(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_small_streams() {
+        let mut top = TopK::new(5);
+        top.extend(vec![2, 1]);
+        assert!(!top.is_full());
+        assert_eq!(top.len(), 2);
+        assert_eq!(top.into_sorted_vec(), vec![2, 1]);
+
+        let mut none = TopK::new(0);
+        none.push(1);
+        assert!(none.is_empty());
+        assert_eq!(none.worst(), None);
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        let scores = [("ann", 30), ("bob", 50), ("eve", 40), ("cat", 30), ("dan", 10)];
+        let mut top = TopK::with_order(3, ByKey(|p: &(&str, u32)| p.1));
+        top.extend(scores.iter().cloned());
+
+        // cat only ties with ann, the worst kept entry, so ann stays
+        let names: Vec<_> = top.into_sorted_vec().into_iter().map(|p| p.0).collect();
+        assert_eq!(names, vec!["bob", "eve", "ann"]);
+    }
+
+    #[test]
+    fn test_merge_shards() {
+        let mut gen = rand::thread_rng();
+        let v: Vec<u32> = (0..10_000).map(|_| gen.gen_range(0, 1_000_000)).collect();
+        let k = 25;
+
+        let mut shards: Vec<_> = v.chunks(1000).map(|chunk| {
+            let mut top = TopK::new(k);
+            top.extend(chunk.iter().cloned());
+            top
+        }).collect();
+
+        let mut total = shards.pop().unwrap();
+        for shard in shards {
+            total.merge(shard);
+        }
+        assert_eq!(total.iter().count(), k);
+
+        let mut expected = v.clone();
+        expected.sort_by(|a, b| b.cmp(a));
+        expected.truncate(k);
+        assert_eq!(total.into_sorted_vec(), expected);
+    }
+}
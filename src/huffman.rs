@@ -0,0 +1,394 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use crate::heap::binary_heap::Heap;
+
+// huffman coding of byte streams.
+//
+// the code is built by repeatedly merging the two least frequent trees, taken from a min
+// heap, until a single tree is left; the depth of a byte in the tree is the length of its
+// code. only the lengths are kept: the codes themselves are canonical, i.e. assigned in
+// order of (length, byte) counting upwards, so a decoder can rebuild them from the lengths.
+//
+// encoded format:
+//     u64 number of bytes of the original data(little endian);
+//     u16 number n of bytes with a code(little endian), followed by n pairs (byte, length);
+//     the codes of the data, packed most significant bit first and padded with 0 bits.
+//
+// references:
+// https://en.wikipedia.org/wiki/Huffman_coding
+// https://en.wikipedia.org/wiki/Canonical_Huffman_code
+
+// code lengths never exceed this. a code of length d requires a total frequency of at least
+// fib(d+2), which bounds the lengths of codes built from u64 frequencies by about 100.
+pub const MAX_CODE_LENGTH: u8 = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    // the input ends before the header or the data is complete
+    Truncated,
+    // the code lengths in the header do not describe a prefix code
+    InvalidCodeLengths,
+    // the data holds a bit sequence that is not a code
+    InvalidCode,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "unexpected end of input"),
+            DecodeError::InvalidCodeLengths => write!(f, "code lengths do not form a prefix code"),
+            DecodeError::InvalidCode => write!(f, "invalid code in the data"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+pub fn frequencies(data: &[u8]) -> [u64; 256] {
+    let mut freq = [0; 256];
+    for &b in data {
+        freq[b as usize] += 1;
+    }
+    freq
+}
+
+// a canonical prefix code for bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HuffmanCode {
+    // 0 for bytes without a code
+    lengths: [u8; 256],
+    codes: [u128; 256],
+}
+
+impl HuffmanCode {
+    // an optimal code for bytes with the given frequencies, bytes with frequency 0 get no code.
+    // a single byte with a nonzero frequency gets a code of length 1.
+    pub fn from_frequencies(freq: &[u64; 256]) -> HuffmanCode {
+        // nodes 0..256 are the leaves, the merged trees are appended.
+        // ties are broken by node id, which makes the code deterministic.
+        let mut parent: Vec<Option<usize>> = vec![None; 256];
+        let mut pq = Heap::new_min();
+        for (b, &f) in freq.iter().enumerate() {
+            if f > 0 {
+                pq.insert((f as u128, b));
+            }
+        }
+
+        let mut lengths = [0; 256];
+        if pq.len() == 1 {
+            let (_, b) = pq.remove().unwrap();
+            lengths[b] = 1;
+        }
+        while pq.len() > 1 {
+            let (fa, a) = pq.remove().unwrap();
+            let (fb, b) = pq.remove().unwrap();
+            let id = parent.len();
+            parent.push(None);
+            parent[a] = Some(id);
+            parent[b] = Some(id);
+            pq.insert((fa + fb, id));
+        }
+
+        // parents have larger ids than their children, so the depths can be computed from
+        // the root downwards
+        let mut depth = vec![0u8; parent.len()];
+        for id in (0..parent.len()).rev() {
+            if let Some(p) = parent[id] {
+                depth[id] = depth[p] + 1;
+            }
+        }
+        for b in 0..256 {
+            if parent[b].is_some() {
+                lengths[b] = depth[b];
+            }
+        }
+
+        HuffmanCode::from_lengths(lengths).expect("huffman code lengths form a prefix code")
+    }
+
+    // the canonical code with the given lengths, 0 meaning no code
+    pub fn from_lengths(lengths: [u8; 256]) -> Result<HuffmanCode, DecodeError> {
+        // Kraft's inequality: a prefix code with these lengths exists iff sum(2^-length) <= 1
+        let mut kraft: u128 = 0;
+        for &len in lengths.iter().filter(|&&len| len > 0) {
+            if len > MAX_CODE_LENGTH {
+                return Err(DecodeError::InvalidCodeLengths);
+            }
+            kraft += 1 << (MAX_CODE_LENGTH - len);
+        }
+        if kraft > 1 << MAX_CODE_LENGTH {
+            return Err(DecodeError::InvalidCodeLengths);
+        }
+
+        let mut codes = [0; 256];
+        let mut code: u128 = 0;
+        let mut prev = 0;
+        for b in sorted_symbols(&lengths) {
+            let len = lengths[b];
+            code <<= len - prev;
+            codes[b] = code;
+            code += 1;
+            prev = len;
+        }
+
+        Ok(HuffmanCode { lengths, codes })
+    }
+
+    // the code of b as (bits, length), the bits in the low length bits
+    pub fn code(&self, b: u8) -> Option<(u128, u8)> {
+        match self.lengths[b as usize] {
+            0 => None,
+            len => Some((self.codes[b as usize], len)),
+        }
+    }
+
+    pub fn lengths(&self) -> &[u8; 256] {
+        &self.lengths
+    }
+
+    // the number of bits the code needs for data with the given frequencies
+    pub fn encoded_bits(&self, freq: &[u64; 256]) -> u64 {
+        freq.iter().zip(self.lengths.iter()).map(|(&f, &len)| f * len as u64).sum()
+    }
+}
+
+// the bytes with a code, ordered by (length, byte)
+fn sorted_symbols(lengths: &[u8; 256]) -> Vec<usize> {
+    let mut symbols: Vec<usize> = (0..256).filter(|&b| lengths[b] > 0).collect();
+    symbols.sort_by_key(|&b| (lengths[b], b));
+    symbols
+}
+
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let code = HuffmanCode::from_frequencies(&frequencies(data));
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    let symbols: Vec<u8> = (0..=255).filter(|&b| code.lengths[b as usize] > 0).collect();
+    out.extend_from_slice(&(symbols.len() as u16).to_le_bytes());
+    for &b in symbols.iter() {
+        out.push(b);
+        out.push(code.lengths[b as usize]);
+    }
+
+    let mut writer = BitWriter { out, used: 0 };
+    for &b in data {
+        let (bits, len) = code.code(b).unwrap();
+        writer.write(bits, len);
+    }
+    writer.out
+}
+
+pub fn decode(input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut reader = ByteReader { input };
+    // the length is untrusted, a length that does not fit is more than the data can hold
+    let len = usize::try_from(u64::from_le_bytes(reader.take()?)).map_err(|_| DecodeError::Truncated)?;
+    let n = u16::from_le_bytes(reader.take()?) as usize;
+    if n > 256 {
+        return Err(DecodeError::InvalidCodeLengths);
+    }
+
+    let mut lengths = [0; 256];
+    for _ in 0..n {
+        let [b, length] = reader.take()?;
+        if length == 0 || lengths[b as usize] != 0 {
+            return Err(DecodeError::InvalidCodeLengths);
+        }
+        lengths[b as usize] = length;
+    }
+    if n == 0 && len > 0 {
+        return Err(DecodeError::InvalidCodeLengths);
+    }
+    // reject lengths that do not form a prefix code
+    HuffmanCode::from_lengths(lengths)?;
+    // every code takes at least one bit, check before allocating the output
+    if len > reader.input.len().saturating_mul(8) {
+        return Err(DecodeError::Truncated);
+    }
+
+    // canonical decoding: the codes of length l are consecutive numbers starting at
+    // first[l], and belong to the symbols symbols[offset[l]..offset[l]+count[l]]
+    let symbols = sorted_symbols(&lengths);
+    let max_len = lengths.iter().cloned().max().unwrap_or(0) as usize;
+    let mut count = vec![0u128; max_len+1];
+    for &b in symbols.iter() {
+        count[lengths[b] as usize] += 1;
+    }
+    let mut first = vec![0u128; max_len+1];
+    let mut offset = vec![0usize; max_len+1];
+    for l in 1..=max_len {
+        first[l] = (first[l-1] + count[l-1]) << 1;
+        offset[l] = offset[l-1] + count[l-1] as usize;
+    }
+
+    let mut bits = reader.input.iter().flat_map(|&byte| (0..8).rev().map(move |i| (byte >> i) & 1));
+    let mut out = Vec::with_capacity(len);
+    while out.len() < len {
+        let mut c: u128 = 0;
+        let mut l = 0;
+        loop {
+            c = (c << 1) | bits.next().ok_or(DecodeError::Truncated)? as u128;
+            l += 1;
+            if l > max_len {
+                return Err(DecodeError::InvalidCode);
+            }
+            if c >= first[l] && c - first[l] < count[l] {
+                out.push(symbols[offset[l] + (c - first[l]) as usize] as u8);
+                break;
+            }
+        }
+    }
+    Ok(out)
+}
+
+struct BitWriter {
+    out: Vec<u8>,
+    // bits used in the last byte of out, 0 when a new byte is needed
+    used: u8,
+}
+
+impl BitWriter {
+    // write the low len bits of bits, most significant first
+    fn write(&mut self, bits: u128, len: u8) {
+        for i in (0..len).rev() {
+            if self.used == 0 {
+                self.out.push(0);
+            }
+            let bit = ((bits >> i) & 1) as u8;
+            *self.out.last_mut().unwrap() |= bit << (7 - self.used);
+            self.used = (self.used + 1) % 8;
+        }
+    }
+}
+
+struct ByteReader<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        if self.input.len() < N {
+            return Err(DecodeError::Truncated);
+        }
+        let (head, rest) = self.input.split_at(N);
+        self.input = rest;
+        let mut res = [0; N];
+        res.copy_from_slice(head);
+        Ok(res)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    fn assert_prefix_free(code: &HuffmanCode) {
+        let coded: Vec<_> = (0..=255).filter_map(|b| code.code(b)).collect();
+        for (i, &(a, la)) in coded.iter().enumerate() {
+            for &(b, lb) in coded[i+1..].iter() {
+                let l = la.min(lb);
+                assert_ne!(a >> (la - l), b >> (lb - l));
+            }
+        }
+    }
+
+    #[test]
+    fn test_code_lengths() {
+        // the classic example: a:45 b:13 c:12 d:16 e:9 f:5
+        let mut freq = [0; 256];
+        for (&b, &f) in b"abcdef".iter().zip([45, 13, 12, 16, 9, 5].iter()) {
+            freq[b as usize] = f;
+        }
+        let code = HuffmanCode::from_frequencies(&freq);
+        let lengths: Vec<_> = b"abcdef".iter().map(|&b| code.lengths()[b as usize]).collect();
+        assert_eq!(lengths, vec![1, 3, 3, 3, 4, 4]);
+        assert_eq!(code.encoded_bits(&freq), 224);
+
+        // canonical: shorter codes first, equal lengths in byte order
+        assert_eq!(code.code(b'a'), Some((0b0, 1)));
+        assert_eq!(code.code(b'b'), Some((0b100, 3)));
+        assert_eq!(code.code(b'c'), Some((0b101, 3)));
+        assert_eq!(code.code(b'd'), Some((0b110, 3)));
+        assert_eq!(code.code(b'e'), Some((0b1110, 4)));
+        assert_eq!(code.code(b'f'), Some((0b1111, 4)));
+        assert_eq!(code.code(b'g'), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut gen = rand::thread_rng();
+        let text = b"it was the best of times, it was the worst of times".to_vec();
+        let skewed: Vec<u8> = (0..10_000).map(|_| if gen.gen_range(0, 10) < 9 { b'x' } else { gen.gen() }).collect();
+        let uniform: Vec<u8> = (0..10_000).map(|_| gen.gen()).collect();
+        let all: Vec<u8> = (0..=255).collect();
+
+        for data in [vec![], vec![7], vec![7; 100], text, skewed, uniform, all].iter() {
+            let encoded = encode(data);
+            assert_eq!(&decode(&encoded).unwrap(), data);
+            assert_prefix_free(&HuffmanCode::from_frequencies(&frequencies(data)));
+        }
+    }
+
+    #[test]
+    fn test_compression() {
+        let data: Vec<u8> = b"aaaaaaab".iter().cycle().take(8000).cloned().collect();
+        let encoded = encode(&data);
+        // 1 bit per byte, plus the header
+        assert_eq!(encoded.len(), 8 + 2 + 4 + 1000);
+    }
+
+    #[test]
+    fn test_fibonacci_frequencies() {
+        // fibonacci frequencies build the deepest possible tree
+        let mut freq = [0; 256];
+        let (mut a, mut b) = (1u64, 1u64);
+        for f in freq.iter_mut().take(60) {
+            *f = a;
+            let next = a + b;
+            a = b;
+            b = next;
+        }
+        let code = HuffmanCode::from_frequencies(&freq);
+        assert_eq!(code.lengths().iter().cloned().max(), Some(59));
+        assert_prefix_free(&code);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let encoded = encode(b"abracadabra");
+        assert_eq!(decode(&encoded[..5]), Err(DecodeError::Truncated));
+        assert_eq!(decode(&encoded[..encoded.len()-1]), Err(DecodeError::Truncated));
+
+        // three codes of length 1 are not a prefix code
+        let mut bad = encoded.clone();
+        bad[8] = 3;
+        bad.truncate(10);
+        bad.extend_from_slice(&[b'a', 1, b'b', 1, b'c', 1]);
+        assert_eq!(decode(&bad), Err(DecodeError::InvalidCodeLengths));
+        assert_eq!(DecodeError::InvalidCodeLengths.to_string(), "code lengths do not form a prefix code");
+
+        // a single code of length 2 leaves bit sequences that are not codes
+        let mut bad = vec![];
+        bad.extend_from_slice(&1u64.to_le_bytes());
+        bad.extend_from_slice(&1u16.to_le_bytes());
+        bad.extend_from_slice(&[b'a', 2, 0b1100_0000]);
+        assert_eq!(decode(&bad), Err(DecodeError::InvalidCode));
+
+        // a forged length far beyond what the data can encode
+        for &len in [u64::MAX, 1 << 40, 17].iter() {
+            let mut bad = vec![];
+            bad.extend_from_slice(&len.to_le_bytes());
+            bad.extend_from_slice(&1u16.to_le_bytes());
+            bad.extend_from_slice(&[b'a', 1, 0]);
+            assert_eq!(decode(&bad), Err(DecodeError::Truncated));
+        }
+    }
+}
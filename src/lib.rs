@@ -1,3 +1,5 @@
+pub mod graph;
 pub mod heap;
+pub mod huffman;
 pub mod sort;
 pub mod tree;
\ No newline at end of file
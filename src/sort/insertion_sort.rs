@@ -17,19 +17,138 @@ pub fn insertion_sort<T: PartialOrd>(v: &mut Vec<T>) -> &Vec<T> {
     v
 }
 
-// rearrange and produce "partially" sorted array that can eventually be sorted by insertion sort
-pub fn shell_sort<T: PartialOrd>(v: &mut Vec<T>) -> &Vec<T> {
+// binary insertion sort finds the insertion point of v[i] in the sorted prefix v[0..i]
+// with binary search, then shifts v[lo..i] one slot to the right in a single pass
+// instead of walking v[i] down with adjacent swaps.
+// it reduces comparisons to O(nlogn) while data movement stays O(n^2).
+pub fn binary_insertion_sort<T: PartialOrd>(v: &mut Vec<T>) -> &Vec<T> {
     if v.len() <= 1 {
         return v;
     }
 
-    let mut h = 1;
-    while h < v.len()/3 {
-        h = 3*h+1;
+    for i in 1..v.len() {
+        // invariant: v[0..lo] <= v[i] and v[hi..i] > v[i];
+        // searching for the upper bound keeps equal elements in their original order.
+        let mut lo = 0;
+        let mut hi = i;
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if v[i] < v[mid] {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+
+        // move v[i] to lo and shift v[lo..i] right by one
+        v[lo..=i].rotate_right(1);
+    }
+
+    v
+}
+
+// gap sequences for shell sort.
+// references:
+// https://en.wikipedia.org/wiki/Shellsort#Gap_sequences
+// https://oeis.org/A003462 (Knuth), https://oeis.org/A033622 (Sedgewick),
+// https://oeis.org/A108870 (Tokuda), https://oeis.org/A102549 (Ciura)
+#[derive(Debug, Clone, Copy)]
+pub enum GapSequence<'a> {
+    // 1, 4, 13, 40, 121, ...: h = 3*h+1
+    Knuth,
+    // 1, 5, 19, 41, 109, 209, 505, ...: interleaves 9*4^k - 9*2^k + 1 and 4^(k+2) - 3*2^(k+2) + 1
+    Sedgewick,
+    // 1, 4, 9, 20, 46, 103, 233, ...: h = ceil(h'), where h' = 2.25*h' + 1
+    Tokuda,
+    // 1, 4, 10, 23, 57, 132, 301, 701, extended by h = floor(2.25*h)
+    Ciura,
+    // user supplied gaps, in any order
+    Custom(&'a [usize]),
+}
+
+impl<'a> GapSequence<'a> {
+    // gaps to be used when shell sorting a vector of length len, in decreasing order.
+    // gaps that are 0 or not less than len are dropped, and the sequence always ends with 1,
+    // so that the last pass is a plain insertion sort.
+    pub fn gaps(&self, len: usize) -> Vec<usize> {
+        let mut gaps = match self {
+            GapSequence::Knuth => {
+                let mut gaps = vec![1];
+                let mut h = 1;
+                while h < len/3 {
+                    h = 3*h+1;
+                    gaps.push(h);
+                }
+                gaps
+            },
+            GapSequence::Sedgewick => {
+                let mut gaps = Vec::new();
+                let mut k = 0;
+                loop {
+                    let even = 9*(1 << (2*k)) - 9*(1 << k) + 1;
+                    if even >= len {
+                        break;
+                    }
+                    gaps.push(even);
+
+                    let odd = (1 << (2*k+4)) - 3*(1 << (k+2)) + 1;
+                    if odd >= len {
+                        break;
+                    }
+                    gaps.push(odd);
+                    k += 1;
+                }
+                gaps
+            },
+            GapSequence::Tokuda => {
+                let mut gaps = Vec::new();
+                let mut h = 1.0f64;
+                while (h.ceil() as usize) < len {
+                    gaps.push(h.ceil() as usize);
+                    h = 2.25*h + 1.0;
+                }
+                gaps
+            },
+            GapSequence::Ciura => {
+                let mut gaps = Vec::new();
+                for &h in [1, 4, 10, 23, 57, 132, 301, 701].iter() {
+                    if h >= len {
+                        break;
+                    }
+                    gaps.push(h);
+                }
+                let mut h = 701;
+                while h < len {
+                    h = h*9/4;
+                    if h < len {
+                        gaps.push(h);
+                    }
+                }
+                gaps
+            },
+            GapSequence::Custom(gaps) => gaps.to_vec(),
+        };
+
+        gaps.retain(|&h| h > 0 && h < len);
+        gaps.push(1);
+        gaps.sort_unstable_by(|a, b| b.cmp(a));
+        gaps.dedup();
+        gaps
+    }
+}
+
+// rearrange and produce "partially" sorted array that can eventually be sorted by insertion sort
+pub fn shell_sort<T: PartialOrd>(v: &mut Vec<T>) -> &Vec<T> {
+    shell_sort_with_gaps(v, GapSequence::Knuth)
+}
+
+pub fn shell_sort_with_gaps<'a, T: PartialOrd>(v: &'a mut Vec<T>, gaps: GapSequence<'_>) -> &'a Vec<T> {
+    if v.len() <= 1 {
+        return v;
     }
 
     // when h == 1, shell sort becomes insertion sort
-    while h >= 1 {
+    for h in gaps.gaps(v.len()) {
         for i in h..v.len() {
             for j in (h..=i).rev().step_by(h) {
                 if v[j] < v[j-h] {
@@ -39,8 +158,6 @@ pub fn shell_sort<T: PartialOrd>(v: &mut Vec<T>) -> &Vec<T> {
                 }
             }
         }
-
-        h /= 3
     }
 
     v
@@ -1,6 +1,10 @@
 use std::cmp;
 
 pub fn merge_sort_top_down<T: PartialOrd + Copy>(v: &mut Vec<T>) -> &Vec<T> {
+    if v.len() <= 1 {
+        return v;
+    }
+
     let mut aux = v.clone();
     sort(v, &mut aux, 0, v.len()-1);
     v
@@ -29,22 +33,23 @@ pub fn merge_sort_bottom_up<T: PartialOrd + Copy>(v: &mut Vec<T>) -> &Vec<T> {
     v
 }
 
-fn sort<T: PartialOrd + Copy>(v: &mut Vec<T>, aux: &mut Vec<T>, lo: usize, hi: usize) {
+// returns the number of inversions in v[lo..=hi] before sorting
+pub(super) fn sort<T: PartialOrd + Copy>(v: &mut Vec<T>, aux: &mut Vec<T>, lo: usize, hi: usize) -> usize {
     if lo >= hi {
-        return
+        return 0
     }
 
     let mid = lo + (hi - lo) / 2;
-    sort(v, aux, lo, mid);
-    sort(v, aux, mid+1, hi);
-    merge(v, aux, lo, mid, hi);
+    sort(v, aux, lo, mid) + sort(v, aux, mid+1, hi) + merge(v, aux, lo, mid, hi)
 }
 
 // prerequisite: both lo..=mid and mid+1..=hi are sorted
-fn merge<T: PartialOrd + Copy>(v: &mut Vec<T>, aux: &mut Vec<T>, lo: usize, mid: usize, hi: usize) {
+// returns the number of inversions between the two halves, i.e. pairs (i, j) with
+// i in lo..=mid, j in mid+1..=hi and v[i] > v[j].
+fn merge<T: PartialOrd + Copy>(v: &mut [T], aux: &mut [T], lo: usize, mid: usize, hi: usize) -> usize {
     // skip if v[lo..=hi] is in sorted order
     if v[mid] <= v[mid+1] {
-        return
+        return 0
     }
 
     // improvement: use two invocations of the sort method;
@@ -54,21 +59,64 @@ fn merge<T: PartialOrd + Copy>(v: &mut Vec<T>, aux: &mut Vec<T>, lo: usize, mid:
         aux[i] = v[i];
     }
 
-    let mut i = lo;
-    let mut j = mid+1;
-    for k in lo..=hi {
-        if i > mid {
-            v[k] = aux[j];
-            j += 1
-        } else if j > hi {
-            v[k] = aux[i];
-            i += 1
-        } else if aux[i] > aux[j] {
-            v[k] = aux[j];
-            j += 1;
+    let mut k = lo;
+    merge_step(&aux[lo..=mid], &aux[mid+1..=hi], |&x| {
+        v[k] = x;
+        k += 1;
+    })
+}
+
+// the merge step, shared with sort::merging::merge: passes the elements of the sorted slices
+// a and b to emit in sorted order. on ties the element from a goes first, so the merge is stable.
+// returns the number of inversions between a and b, i.e. pairs (i, j) with a[i] > b[j].
+pub(super) fn merge_step<'a, T, F>(a: &'a [T], b: &'a [T], emit: F) -> usize
+where
+    T: PartialOrd,
+    F: FnMut(&'a T),
+{
+    // the guard emits whatever is left in a and b when it goes out of scope,
+    // including when a comparison panics halfway through the merge.
+    let mut hole = MergeHole {
+        a,
+        b,
+        i: 0,
+        j: 0,
+        emit,
+    };
+    let mut inversions = 0;
+    while hole.i < a.len() && hole.j < b.len() {
+        if a[hole.i] > b[hole.j] {
+            // b[j] is smaller than all of a[i..]
+            inversions += a.len() - hole.i;
+            (hole.emit)(&b[hole.j]);
+            hole.j += 1;
         } else {
-            v[k] = aux[i];
-            i += 1;
+            (hole.emit)(&a[hole.i]);
+            hole.i += 1;
+        }
+    }
+
+    inversions
+}
+
+// panic safety:
+// during a merge sort's merge v[lo..k] holds the merged prefix, while the elements that are not
+// merged yet only exist in aux[i..=mid] and aux[j..=hi]; v[k..=hi] holds stale copies.
+// if a comparison panics, v would be left with duplicated and lost elements.
+// dropping the hole emits the remaining elements into v[k..=hi], so that v is always
+// a permutation of its input, whether the merge finished or unwound.
+struct MergeHole<'a, T, F: FnMut(&'a T)> {
+    a: &'a [T],
+    b: &'a [T],
+    i: usize,
+    j: usize,
+    emit: F,
+}
+
+impl<'a, T, F: FnMut(&'a T)> Drop for MergeHole<'a, T, F> {
+    fn drop(&mut self) {
+        for x in self.a[self.i..].iter().chain(&self.b[self.j..]) {
+            (self.emit)(x);
         }
     }
 }
@@ -0,0 +1,369 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+
+use crate::heap::binary_heap::Heap;
+use crate::heap::order::Min;
+
+use super::merge_sort::merge_step;
+
+// merging and set operations on sorted sequences.
+// all inputs are expected to be sorted in non-decreasing order; the results are
+// unspecified (but memory safe) otherwise.
+//
+// set operations treat their inputs as multisets, following std::set_union & co. in C++:
+// if x occurs m times in a and n times in b, it occurs
+//     max(m, n) times in union(a, b);
+//     min(m, n) times in intersection(a, b);
+//     max(m-n, 0) times in difference(a, b);
+//     |m-n| times in symmetric_difference(a, b).
+
+////////////////////////////////////////////////////////////////////////////////
+// two-way merge
+////////////////////////////////////////////////////////////////////////////////
+
+// this is the merge step of merge sort on two separate slices:
+// on ties the element from a goes first, so the merge is stable.
+pub fn merge<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    // collect references first and clone afterwards, so that a panicking clone does not
+    // run into the merge step's guard
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    merge_step(a, b, |x| merged.push(x));
+    merged.into_iter().cloned().collect()
+}
+
+// lazily merge two sorted iterators
+pub fn merge_iter<I, J>(a: I, b: J) -> Merge<I::IntoIter, J::IntoIter>
+where
+    I: IntoIterator,
+    J: IntoIterator<Item = I::Item>,
+    I::Item: PartialOrd,
+{
+    Merge {
+        a: a.into_iter().peekable(),
+        b: b.into_iter().peekable(),
+    }
+}
+
+pub struct Merge<I: Iterator, J: Iterator<Item = I::Item>> {
+    a: Peekable<I>,
+    b: Peekable<J>,
+}
+
+impl<I, J> Iterator for Merge<I, J>
+where
+    I: Iterator,
+    J: Iterator<Item = I::Item>,
+    I::Item: PartialOrd,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let take_b = match (self.a.peek(), self.b.peek()) {
+            (Some(x), Some(y)) => y < x,
+            (None, _) => true,
+            (_, None) => false,
+        };
+
+        if take_b {
+            self.b.next()
+        } else {
+            self.a.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lo, a_hi) = self.a.size_hint();
+        let (b_lo, b_hi) = self.b.size_hint();
+        let hi = match (a_hi, b_hi) {
+            (Some(x), Some(y)) => x.checked_add(y),
+            _ => None,
+        };
+        (a_lo.saturating_add(b_lo), hi)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// set operations
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn union<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut res = Vec::with_capacity(a.len() + b.len());
+    let mut i = 0;
+    let mut j = 0;
+
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            res.push(a[i].clone());
+            i += 1;
+        } else if b[j] < a[i] {
+            res.push(b[j].clone());
+            j += 1;
+        } else {
+            res.push(a[i].clone());
+            i += 1;
+            j += 1;
+        }
+    }
+    res.extend_from_slice(&a[i..]);
+    res.extend_from_slice(&b[j..]);
+
+    res
+}
+
+pub fn intersection<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut res = Vec::new();
+    let mut i = 0;
+    let mut j = 0;
+
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            i += 1;
+        } else if b[j] < a[i] {
+            j += 1;
+        } else {
+            res.push(a[i].clone());
+            i += 1;
+            j += 1;
+        }
+    }
+
+    res
+}
+
+// elements of a that are not in b
+pub fn difference<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut res = Vec::new();
+    let mut i = 0;
+    let mut j = 0;
+
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            res.push(a[i].clone());
+            i += 1;
+        } else if b[j] < a[i] {
+            j += 1;
+        } else {
+            i += 1;
+            j += 1;
+        }
+    }
+    res.extend_from_slice(&a[i..]);
+
+    res
+}
+
+// elements that are in exactly one of a and b
+pub fn symmetric_difference<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut res = Vec::new();
+    let mut i = 0;
+    let mut j = 0;
+
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            res.push(a[i].clone());
+            i += 1;
+        } else if b[j] < a[i] {
+            res.push(b[j].clone());
+            j += 1;
+        } else {
+            i += 1;
+            j += 1;
+        }
+    }
+    res.extend_from_slice(&a[i..]);
+    res.extend_from_slice(&b[j..]);
+
+    res
+}
+
+// keep the first of every run of equal elements
+pub fn dedup<T: PartialOrd + Clone>(v: &[T]) -> Vec<T> {
+    let mut res: Vec<T> = Vec::with_capacity(v.len());
+    for x in v {
+        match res.last() {
+            Some(last) if last == x => (),
+            _ => res.push(x.clone()),
+        }
+    }
+
+    res
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// k-way merge
+////////////////////////////////////////////////////////////////////////////////
+
+// k-way merge keeps the current head of every source in a heap, so that each
+// element is produced in O(logk). ties are resolved in favor of the source that
+// comes first, which makes the merge stable.
+pub fn kmerge<I>(sources: I) -> KMerge<<I::Item as IntoIterator>::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    <I::Item as IntoIterator>::Item: PartialOrd,
+{
+    let mut sources: Vec<_> = sources.into_iter().map(|s| s.into_iter()).collect();
+    let mut heap = Heap::new_min();
+    for (source, iter) in sources.iter_mut().enumerate() {
+        if let Some(item) = iter.next() {
+            heap.insert(Head { item, source });
+        }
+    }
+
+    KMerge {
+        sources,
+        heap,
+    }
+}
+
+pub struct KMerge<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    sources: Vec<I>,
+    heap: Heap<Head<I::Item>, Min>,
+}
+
+impl<I> Iterator for KMerge<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let head = self.heap.remove()?;
+        if let Some(item) = self.sources[head.source].next() {
+            self.heap.insert(Head { item, source: head.source });
+        }
+
+        Some(head.item)
+    }
+}
+
+// heads are ordered by item, then by source, so that the first source wins ties
+struct Head<T> {
+    item: T,
+    source: usize,
+}
+
+impl<T: PartialOrd> PartialEq for Head<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Head<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.item.partial_cmp(&other.item) {
+            Some(Ordering::Equal) => Some(self.source.cmp(&other.source)),
+            ord => ord,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(merge::<i32>(&[], &[]), vec![]);
+        assert_eq!(merge(&[1,3,5], &[]), vec![1,3,5]);
+        assert_eq!(merge(&[], &[2,4]), vec![2,4]);
+        assert_eq!(merge(&[1,3,5], &[2,3,4,6]), vec![1,2,3,3,4,5,6]);
+        assert_eq!(merge(&["a", "c"], &["b"]), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_merge_iter() {
+        let merged: Vec<_> = merge_iter(vec![1,3,5], vec![2,3,4,6]).collect();
+        assert_eq!(merged, vec![1,2,3,3,4,5,6]);
+
+        let merged: Vec<_> = merge_iter((0..10).filter(|x| x % 2 == 0), 5..8).collect();
+        assert_eq!(merged, vec![0,2,4,5,6,6,7,8]);
+
+        // ties are taken from the first iterator first
+        let a = vec![Keyed { key: 1, tag: 0 }, Keyed { key: 2, tag: 0 }];
+        let b = vec![Keyed { key: 1, tag: 1 }, Keyed { key: 2, tag: 1 }];
+        let merged: Vec<_> = merge_iter(a, b).map(|k| (k.key, k.tag)).collect();
+        assert_eq!(merged, vec![(1,0), (1,1), (2,0), (2,1)]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = [1,2,2,2,4,6];
+        let b = [2,2,3,4,7];
+
+        assert_eq!(union(&a, &b), vec![1,2,2,2,3,4,6,7]);
+        assert_eq!(intersection(&a, &b), vec![2,2,4]);
+        assert_eq!(difference(&a, &b), vec![1,2,6]);
+        assert_eq!(difference(&b, &a), vec![3,7]);
+        assert_eq!(symmetric_difference(&a, &b), vec![1,2,3,6,7]);
+
+        assert_eq!(union(&a, &[]), a.to_vec());
+        assert_eq!(intersection(&a, &[]), vec![]);
+        assert_eq!(difference(&[], &a), vec![]);
+        assert_eq!(symmetric_difference(&[], &b), b.to_vec());
+    }
+
+    #[test]
+    fn test_dedup() {
+        assert_eq!(dedup::<i32>(&[]), vec![]);
+        assert_eq!(dedup(&[1,1,1]), vec![1]);
+        assert_eq!(dedup(&[1,2,2,3,3,3,4]), vec![1,2,3,4]);
+    }
+
+    #[test]
+    fn test_kmerge() {
+        let merged: Vec<i32> = kmerge(Vec::<Vec<i32>>::new()).collect();
+        assert_eq!(merged, vec![]);
+
+        let merged: Vec<_> = kmerge(vec![vec![1,4,7], vec![], vec![2,5,8], vec![0,3,6,9]]).collect();
+        assert_eq!(merged, (0..10).collect::<Vec<_>>());
+
+        let merged: Vec<_> = kmerge((0..5).map(|i| (i..20).step_by(5))).collect();
+        let mut expected: Vec<_> = (0..5).flat_map(|i| (i..20).step_by(5)).collect();
+        expected.sort();
+        assert_eq!(merged, expected);
+
+        let words = vec![vec!["apple".to_string(), "melon".to_string()], vec!["kiwi".to_string()]];
+        let merged: Vec<_> = kmerge(words).collect();
+        assert_eq!(merged, vec!["apple", "kiwi", "melon"]);
+    }
+
+    #[test]
+    fn test_kmerge_stability() {
+        let sources = vec![
+            vec![Keyed { key: 1, tag: 0 }, Keyed { key: 3, tag: 0 }],
+            vec![Keyed { key: 1, tag: 1 }, Keyed { key: 2, tag: 1 }, Keyed { key: 3, tag: 1 }],
+            vec![Keyed { key: 1, tag: 2 }, Keyed { key: 3, tag: 2 }],
+        ];
+        let merged: Vec<_> = kmerge(sources).map(|k| (k.key, k.tag)).collect();
+        assert_eq!(merged, vec![(1,0), (1,1), (1,2), (2,1), (3,0), (3,1), (3,2)]);
+    }
+
+    // compares by key only, so that the tag exposes the order of equal keys
+    #[derive(Clone, Copy)]
+    struct Keyed {
+        key: i32,
+        tag: usize,
+    }
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            self.key.partial_cmp(&other.key)
+        }
+    }
+}
@@ -1,7 +1,12 @@
 pub mod heap_sort;
 pub mod insertion_sort;
 pub mod merge_sort;
+pub mod merging;
 pub mod quick_sort;
+pub mod sortedness;
+
+#[cfg(test)]
+mod property;
 
 #[cfg(test)]
 mod tests {
@@ -9,10 +14,17 @@ mod tests {
 
     use super::heap_sort::heap_sort;
     use super::insertion_sort::insertion_sort;
+    use super::insertion_sort::binary_insertion_sort;
     use super::insertion_sort::shell_sort;
+    use super::insertion_sort::shell_sort_with_gaps;
+    use super::insertion_sort::GapSequence;
     use super::merge_sort::merge_sort_top_down;
     use super::merge_sort::merge_sort_bottom_up;
     use super::quick_sort::quick_sort_basic;
+    use super::quick_sort::quick_sort_3way_partition;
+    use super::property::check;
+    use super::property::check_panic_safety;
+    use super::sortedness::is_sorted;
 
     fn generate_random_large(size: usize) -> Vec<usize> {
         let mut gen = rand::thread_rng();
@@ -26,10 +38,8 @@ mod tests {
         v
     }
 
-    fn assert_sorted<T: PartialOrd>(v: &Vec<T>) {
-        for i in 0..v.len()-1 {
-            assert!(v[i] <= v[i+1]);
-        }
+    fn assert_sorted<T: PartialOrd>(v: &[T]) {
+        assert!(is_sorted(v));
     }
 
     #[test]
@@ -50,6 +60,15 @@ mod tests {
         assert_sorted(insertion_sort(&mut generate_random_large(10000)));
     }
 
+    #[test]
+    fn test_binary_insertion_sort() {
+        assert_sorted(binary_insertion_sort(&mut vec![1]));
+        assert_sorted(binary_insertion_sort(&mut vec![1,1,1,1,1]));
+        assert_sorted(binary_insertion_sort(&mut vec![4,2,6,7,1,10]));
+        assert_sorted(binary_insertion_sort(&mut vec!["w", "h", "p", "a", "x"]));
+        assert_sorted(binary_insertion_sort(&mut generate_random_large(10000)));
+    }
+
     #[test]
     fn test_shell_sort() {
         assert_sorted(shell_sort(&mut vec![1]));
@@ -59,6 +78,36 @@ mod tests {
         assert_sorted(shell_sort(&mut generate_random_large(10000)));
     }
 
+    #[test]
+    fn test_shell_sort_with_gaps() {
+        let sequences = [
+            GapSequence::Knuth,
+            GapSequence::Sedgewick,
+            GapSequence::Tokuda,
+            GapSequence::Ciura,
+            GapSequence::Custom(&[7, 3]),
+            GapSequence::Custom(&[]),
+        ];
+
+        for &gaps in sequences.iter() {
+            assert_sorted(shell_sort_with_gaps(&mut vec![1], gaps));
+            assert_sorted(shell_sort_with_gaps(&mut vec![1,1,1,1,1], gaps));
+            assert_sorted(shell_sort_with_gaps(&mut vec![4,2,6,7,1,10], gaps));
+            assert_sorted(shell_sort_with_gaps(&mut vec!["w", "h", "p", "a", "x"], gaps));
+            assert_sorted(shell_sort_with_gaps(&mut generate_random_large(10000), gaps));
+        }
+    }
+
+    #[test]
+    fn test_gap_sequences() {
+        assert_eq!(GapSequence::Knuth.gaps(1000), vec![364, 121, 40, 13, 4, 1]);
+        assert_eq!(GapSequence::Sedgewick.gaps(1000), vec![929, 505, 209, 109, 41, 19, 5, 1]);
+        assert_eq!(GapSequence::Tokuda.gaps(1000), vec![525, 233, 103, 46, 20, 9, 4, 1]);
+        assert_eq!(GapSequence::Ciura.gaps(2000), vec![1577, 701, 301, 132, 57, 23, 10, 4, 1]);
+        assert_eq!(GapSequence::Custom(&[1, 8, 0, 3, 8, 50]).gaps(20), vec![8, 3, 1]);
+        assert_eq!(GapSequence::Ciura.gaps(1), vec![1]);
+    }
+
     #[test]
     fn test_merge_sort_top_down() {
         assert_sorted(merge_sort_top_down(&mut vec![1]));
@@ -85,4 +134,52 @@ mod tests {
         assert_sorted(quick_sort_basic(&mut vec!["w", "h", "p", "a", "x"]));
         assert_sorted(quick_sort_basic(&mut generate_random_large(10000)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_quick_sort_3way_partition() {
+        assert_sorted(quick_sort_3way_partition(&mut vec![1]));
+        assert_sorted(quick_sort_3way_partition(&mut vec![1,1,1,1,1]));
+        assert_sorted(quick_sort_3way_partition(&mut vec![4,2,6,7,1,10]));
+        assert_sorted(quick_sort_3way_partition(&mut vec!["w", "h", "p", "a", "x"]));
+        assert_sorted(quick_sort_3way_partition(&mut generate_random_large(10000)));
+    }
+
+    #[test]
+    fn test_properties() {
+        check("heap_sort", false, |v| { heap_sort(v); });
+        check("insertion_sort", true, |v| { insertion_sort(v); });
+        check("binary_insertion_sort", true, |v| { binary_insertion_sort(v); });
+        check("shell_sort", false, |v| { shell_sort(v); });
+        check("merge_sort_top_down", true, |v| { merge_sort_top_down(v); });
+        check("merge_sort_bottom_up", true, |v| { merge_sort_bottom_up(v); });
+        check("quick_sort_basic", false, |v| { quick_sort_basic(v); });
+        check("quick_sort_3way_partition", false, |v| { quick_sort_3way_partition(v); });
+    }
+
+    #[test]
+    fn test_shell_sort_gap_sequence_properties() {
+        let sequences = [
+            GapSequence::Knuth,
+            GapSequence::Sedgewick,
+            GapSequence::Tokuda,
+            GapSequence::Ciura,
+            GapSequence::Custom(&[5, 2]),
+        ];
+
+        for &gaps in sequences.iter() {
+            check(&format!("shell_sort_with_gaps({:?})", gaps), false, |v| { shell_sort_with_gaps(v, gaps); });
+        }
+    }
+
+    #[test]
+    fn test_panic_safety() {
+        check_panic_safety("heap_sort", |v| { heap_sort(v); });
+        check_panic_safety("insertion_sort", |v| { insertion_sort(v); });
+        check_panic_safety("binary_insertion_sort", |v| { binary_insertion_sort(v); });
+        check_panic_safety("shell_sort", |v| { shell_sort(v); });
+        check_panic_safety("merge_sort_top_down", |v| { merge_sort_top_down(v); });
+        check_panic_safety("merge_sort_bottom_up", |v| { merge_sort_bottom_up(v); });
+        check_panic_safety("quick_sort_basic", |v| { quick_sort_basic(v); });
+        check_panic_safety("quick_sort_3way_partition", |v| { quick_sort_3way_partition(v); });
+    }
+}
@@ -0,0 +1,381 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use rand::{Rng, SeedableRng, StdRng};
+
+// a lightweight property-based test harness for sorting algorithms.
+//
+// every algorithm is run against inputs drawn from several distributions of keys.
+// keys are tagged with their original position, and the output is checked to be:
+//     a permutation of the input: every tag appears exactly once, with its original key;
+//     in order: keys are non-decreasing;
+//     stable (for stable algorithms): tags of equal keys are increasing.
+// a failing input is shrunk to a minimal counterexample before being reported,
+// together with the seed that reproduces it.
+
+////////////////////////////////////////////////////////////////////////////////
+// tagged element
+////////////////////////////////////////////////////////////////////////////////
+
+// elements are compared by key only, the tag records the original position
+#[derive(Debug, Clone, Copy)]
+pub struct Tagged {
+    pub key: u32,
+    pub tag: usize,
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+pub fn tag(keys: &[u32]) -> Vec<Tagged> {
+    keys.iter().enumerate().map(|(tag, &key)| Tagged { key, tag }).collect()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// input distributions
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, Copy)]
+pub enum Distribution {
+    Random,
+    Sorted,
+    Reversed,
+    AllEqual,
+    FewUnique,
+    OrganPipe,
+    Sawtooth,
+    NearlySorted,
+}
+
+pub const DISTRIBUTIONS: [Distribution; 8] = [
+    Distribution::Random,
+    Distribution::Sorted,
+    Distribution::Reversed,
+    Distribution::AllEqual,
+    Distribution::FewUnique,
+    Distribution::OrganPipe,
+    Distribution::Sawtooth,
+    Distribution::NearlySorted,
+];
+
+impl Distribution {
+    pub fn generate<R: Rng>(self, len: usize, rng: &mut R) -> Vec<u32> {
+        let n = len as u32;
+        let mut keys: Vec<u32> = match self {
+            Distribution::Random => (0..len).map(|_| rng.gen_range(0, n + 1)).collect(),
+            Distribution::Sorted => (0..n).collect(),
+            Distribution::Reversed => (0..n).rev().collect(),
+            Distribution::AllEqual => vec![7; len],
+            Distribution::FewUnique => (0..len).map(|_| rng.gen_range(0, 4)).collect(),
+            Distribution::OrganPipe => (0..n).map(|i| if i < n/2 { i } else { n - i }).collect(),
+            Distribution::Sawtooth => (0..n).map(|i| i % 5).collect(),
+            Distribution::NearlySorted => (0..n).collect(),
+        };
+
+        if let Distribution::NearlySorted = self {
+            for _ in 0..len/10 + 1 {
+                if len > 1 {
+                    let i = rng.gen_range(0, len);
+                    let j = rng.gen_range(0, len);
+                    keys.swap(i, j);
+                }
+            }
+        }
+
+        keys
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// properties
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub enum Violation {
+    Panicked,
+    NotPermutation,
+    NotSorted(usize),
+    NotStable(usize),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Panicked => write!(f, "sort panicked"),
+            Violation::NotPermutation => write!(f, "output is not a permutation of the input"),
+            Violation::NotSorted(i) => write!(f, "output[{}] > output[{}]", i, i+1),
+            Violation::NotStable(i) => write!(f, "equal keys at output[{}] and output[{}] swapped", i, i+1),
+        }
+    }
+}
+
+// check that output is a permutation of input, and ordered by key.
+// input must be tagged with positions 0..input.len().
+pub fn check_output(input: &[Tagged], output: &[Tagged], stable: bool) -> Result<(), Violation> {
+    if input.len() != output.len() {
+        return Err(Violation::NotPermutation);
+    }
+
+    let mut seen = vec![false; input.len()];
+    for x in output {
+        if x.tag >= input.len() || seen[x.tag] || input[x.tag].key != x.key {
+            return Err(Violation::NotPermutation);
+        }
+        seen[x.tag] = true;
+    }
+
+    for i in 1..output.len() {
+        if output[i-1].key > output[i].key {
+            return Err(Violation::NotSorted(i-1));
+        }
+        if stable && output[i-1].key == output[i].key && output[i-1].tag > output[i].tag {
+            return Err(Violation::NotStable(i-1));
+        }
+    }
+
+    Ok(())
+}
+
+fn run<F: Fn(&mut Vec<Tagged>)>(sort: &F, keys: &[u32], stable: bool) -> Result<(), Violation> {
+    let input = tag(keys);
+    let mut output = input.clone();
+    match panic::catch_unwind(AssertUnwindSafe(|| sort(&mut output))) {
+        Ok(()) => check_output(&input, &output, stable),
+        Err(_) => Err(Violation::Panicked),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// shrinking
+////////////////////////////////////////////////////////////////////////////////
+
+// greedily shrink a failing input: first try to drop chunks of elements,
+// then try to make the remaining keys smaller, until no candidate fails anymore.
+pub fn shrink<P: Fn(&[u32]) -> bool>(keys: &[u32], fails: P) -> Vec<u32> {
+    let mut keys = keys.to_vec();
+
+    loop {
+        let mut progress = false;
+
+        let mut chunk = keys.len()/2;
+        while chunk >= 1 {
+            let mut start = 0;
+            while start + chunk <= keys.len() {
+                let mut candidate = keys.clone();
+                candidate.drain(start..start+chunk);
+                if fails(&candidate) {
+                    keys = candidate;
+                    progress = true;
+                } else {
+                    start += chunk;
+                }
+            }
+            chunk /= 2;
+        }
+
+        for i in 0..keys.len() {
+            for &smaller in [0, keys[i]/2, keys[i].saturating_sub(1)].iter() {
+                if smaller >= keys[i] {
+                    continue;
+                }
+
+                let mut candidate = keys.clone();
+                candidate[i] = smaller;
+                if fails(&candidate) {
+                    keys = candidate;
+                    progress = true;
+                    break;
+                }
+            }
+        }
+
+        if !progress {
+            return keys;
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// driver
+////////////////////////////////////////////////////////////////////////////////
+
+pub const MAX_LEN: usize = 300;
+pub const CASES: usize = 20;
+
+// run sort against CASES inputs of random length for every distribution,
+// plus every length up to 8, and panic with a shrunk counterexample on failure.
+pub fn check<F: Fn(&mut Vec<Tagged>)>(name: &str, stable: bool, sort: F) {
+    let seed = rand::thread_rng().gen();
+    check_with_seed(name, stable, seed, sort);
+}
+
+pub fn check_with_seed<F: Fn(&mut Vec<Tagged>)>(name: &str, stable: bool, seed: u64, sort: F) {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for &dist in DISTRIBUTIONS.iter() {
+        let lens = (0..=8).chain((0..CASES).map(|_| rng.gen_range(0, MAX_LEN + 1))).collect::<Vec<_>>();
+        for len in lens {
+            let keys = dist.generate(len, &mut rng);
+            if let Err(violation) = run(&sort, &keys, stable) {
+                let minimal = shrink(&keys, |candidate| run(&sort, candidate, stable).is_err());
+                let violation = run(&sort, &minimal, stable).err().unwrap_or(violation);
+                panic!(
+                    "{} failed on {:?} input (seed {}): {}\nminimal input: {:?}",
+                    name, dist, seed, violation, minimal,
+                );
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// panic safety
+////////////////////////////////////////////////////////////////////////////////
+
+// a comparison that panics must not leave the slice with duplicated or lost elements.
+// Bomb is a tagged element whose comparisons are counted, and which panics on the
+// comparison selected by arm().
+
+thread_local! {
+    static COMPARISONS: Cell<usize> = const { Cell::new(0) };
+    static FUSE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Bomb {
+    pub key: u32,
+    pub tag: usize,
+}
+
+// the comparison count is reset, and the nth comparison from now on panics
+fn arm(nth: Option<usize>) {
+    COMPARISONS.with(|c| c.set(0));
+    FUSE.with(|f| f.set(nth));
+}
+
+fn comparisons() -> usize {
+    COMPARISONS.with(|c| c.get())
+}
+
+impl PartialEq for Bomb {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Bomb {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let count = COMPARISONS.with(|c| {
+            c.set(c.get() + 1);
+            c.get()
+        });
+        if FUSE.with(|f| f.get()) == Some(count) {
+            // resume_unwind skips the panic hook, which keeps the test output clean
+            panic::resume_unwind(Box::new("injected panic"));
+        }
+
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+// inject a panic at random comparisons while sorting inputs from every distribution,
+// and check that the unwound vector is still a permutation of its input.
+pub fn check_panic_safety<F: Fn(&mut Vec<Bomb>)>(name: &str, sort: F) {
+    let seed = rand::thread_rng().gen();
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for &dist in DISTRIBUTIONS.iter() {
+        for _ in 0..CASES {
+            let len = rng.gen_range(2, MAX_LEN + 1);
+            let keys = dist.generate(len, &mut rng);
+            let input: Vec<Bomb> = tag(&keys).into_iter().map(|t| Bomb { key: t.key, tag: t.tag }).collect();
+
+            // count the comparisons of a complete run first
+            arm(None);
+            sort(&mut input.clone());
+            let total = comparisons();
+            if total == 0 {
+                continue;
+            }
+
+            // randomized algorithms may compare less often on the second run, so the fuse
+            // might not blow; the output has to be a permutation either way.
+            let nth = rng.gen_range(1, total + 1);
+            let mut output = input.clone();
+            arm(Some(nth));
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| sort(&mut output)));
+            arm(None);
+
+            let mut tags: Vec<_> = output.iter().map(|b| b.tag).collect();
+            tags.sort_unstable();
+            let permutation = tags.iter().enumerate().all(|(i, &t)| i == t)
+                && output.iter().all(|b| input[b.tag].key == b.key);
+            assert!(
+                permutation,
+                "{} is not panic safe on {:?} input (seed {}) with a panic at comparison {} of {}",
+                name, dist, seed, nth, total,
+            );
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_output() {
+        let input = tag(&[2, 1, 2]);
+        let sorted = vec![input[1], input[0], input[2]];
+        assert!(check_output(&input, &sorted, true).is_ok());
+
+        let unstable = vec![input[1], input[2], input[0]];
+        assert!(check_output(&input, &unstable, false).is_ok());
+        assert!(check_output(&input, &unstable, true).is_err());
+
+        let duplicated = vec![input[1], input[0], input[0]];
+        assert!(check_output(&input, &duplicated, false).is_err());
+
+        let unsorted = vec![input[0], input[1], input[2]];
+        assert!(check_output(&input, &unsorted, false).is_err());
+    }
+
+    #[test]
+    fn test_shrink() {
+        // fails whenever a key larger than 10 is present
+        let minimal = shrink(&[3, 40, 1, 27, 5, 90], |keys| keys.iter().any(|&k| k > 10));
+        assert_eq!(minimal, vec![11]);
+    }
+
+    #[test]
+    #[should_panic(expected = "minimal input: [1, 0]")]
+    fn test_check_reports_minimal_input() {
+        // "sorts" by doing nothing
+        check_with_seed("identity", false, 0, |_| ());
+    }
+
+    #[test]
+    #[should_panic(expected = "is not panic safe")]
+    fn test_check_panic_safety_detects_duplicates() {
+        // copy the first element over the second, then compare
+        check_panic_safety("duplicating", |v| {
+            v[1] = v[0];
+            let _ = v[0] < v[1];
+        });
+    }
+}
@@ -1,12 +1,20 @@
 use rand::Rng;
 
 pub fn quick_sort_basic<T: PartialOrd>(v: &mut Vec<T>) -> &Vec<T> {
+    if v.len() <= 1 {
+        return v;
+    }
+
     shuffle(v);
     sort_basic(v, 0, v.len()-1);
     v
 }
 
 pub fn quick_sort_3way_partition<T: PartialOrd>(v: &mut Vec<T>) -> &Vec<T> {
+    if v.len() <= 1 {
+        return v;
+    }
+
     shuffle(v);
     sort_3way_partition(v, 0, v.len()-1);
     v
@@ -38,6 +46,8 @@ fn sort_basic<T: PartialOrd>(v: &mut Vec<T>, lo: usize, hi: usize) {
 //   v[lt..i]     - equal to pivot(v[lt] == pivot);
 //   v[i..=gt]    - to be examined(including gt);
 //   v[gt+1..=hi] - larger than pivot
+//
+// the pivot starts at v[lo] and moves along with lt, so it is always compared as v[lt].
 fn sort_3way_partition<T: PartialOrd>(v: &mut Vec<T>, lo: usize, hi: usize) {
     if lo >= hi {
         return;
@@ -49,19 +59,21 @@ fn sort_3way_partition<T: PartialOrd>(v: &mut Vec<T>, lo: usize, hi: usize) {
     let mut gt = hi;
     
     while i <= gt {
-        if v[i] < v[lo] {
+        if v[i] < v[lt] {
             v.swap(i, lt);
             i += 1;
             lt += 1;
-        } else if v[i] > v[lo] {
+        } else if v[i] > v[lt] {
             v.swap(i, gt);
             gt -= 1;
-        } else /* v[i] == v[lo] */ {
+        } else /* v[i] == v[lt] */ {
             i += 1;
         }
     }
 
-    sort_3way_partition(v, lo, lt);
+    if lt > lo {
+        sort_3way_partition(v, lo, lt-1);
+    }
     sort_3way_partition(v, gt+1, hi);
 }
 
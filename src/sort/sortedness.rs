@@ -0,0 +1,148 @@
+use std::cmp::Ordering;
+
+use super::merge_sort;
+
+// measures of presortedness quantify how far a sequence is from being sorted.
+// adaptive sorting algorithms run faster on inputs with low disorder, e.g.
+// insertion sort runs in O(n + inv) where inv is the number of inversions,
+// and natural merge sort runs in O(nlog(runs)).
+//
+// references:
+// https://en.wikipedia.org/wiki/Adaptive_sort
+// Estivill-Castro and Wood, A Survey of Adaptive Sorting Algorithms (1992)
+
+pub fn is_sorted<T: PartialOrd>(v: &[T]) -> bool {
+    v.windows(2).all(|w| w[0] <= w[1])
+}
+
+// v is sorted by compare if no element compares Greater than its successor
+pub fn is_sorted_by<T, F>(v: &[T], mut compare: F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    v.windows(2).all(|w| compare(&w[0], &w[1]) != Ordering::Greater)
+}
+
+// number of pairs (i, j) with i < j and v[i] > v[j], counted in O(nlogn)
+// while merge sorting a copy of v.
+pub fn count_inversions<T: PartialOrd + Copy>(v: &[T]) -> usize {
+    if v.len() <= 1 {
+        return 0;
+    }
+
+    let mut v = v.to_vec();
+    let mut aux = v.clone();
+    let hi = v.len()-1;
+    merge_sort::sort(&mut v, &mut aux, 0, hi)
+}
+
+// number of maximal non-decreasing runs, e.g. [1,2,3,2,5,4] has runs [1,2,3], [2,5], [4].
+// a sorted sequence has 1 run, a strictly decreasing one has v.len() runs.
+pub fn count_runs<T: PartialOrd>(v: &[T]) -> usize {
+    if v.is_empty() {
+        return 0;
+    }
+
+    1 + v.windows(2).filter(|w| w[0] > w[1]).count()
+}
+
+// length of the longest non-decreasing subsequence, computed with patience sorting in O(nlogn).
+// invariant: v[tails[k]] is the smallest possible last element of a subsequence of length k+1,
+// so v[tails[..]] is non-decreasing.
+pub fn longest_increasing_subsequence<T: PartialOrd>(v: &[T]) -> usize {
+    let mut tails: Vec<usize> = Vec::new();
+
+    for (i, x) in v.iter().enumerate() {
+        // find the first tail that is greater than x
+        let mut lo = 0;
+        let mut hi = tails.len();
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if *x < v[tails[mid]] {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+
+        if lo == tails.len() {
+            tails.push(i);
+        } else {
+            tails[lo] = i;
+        }
+    }
+
+    tails.len()
+}
+
+// minimum number of elements to remove to leave a sorted sequence
+pub fn count_removals<T: PartialOrd>(v: &[T]) -> usize {
+    v.len() - longest_increasing_subsequence(v)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_sorted() {
+        assert!(is_sorted::<i32>(&[]));
+        assert!(is_sorted(&[1]));
+        assert!(is_sorted(&[1,1,2,3,3]));
+        assert!(!is_sorted(&[1,3,2]));
+        assert!(!is_sorted(&[1.0, f64::NAN, 2.0]));
+
+        assert!(is_sorted_by(&[3,2,2,1], |a, b| b.cmp(a)));
+        assert!(!is_sorted_by(&[1,2], |a, b| b.cmp(a)));
+        assert!(!is_sorted_by(&["bb", "a", "ccc"], |a, b| a.len().cmp(&b.len())));
+        assert!(is_sorted_by(&["a", "cc", "bb"], |a, b| a.len().cmp(&b.len())));
+    }
+
+    #[test]
+    fn test_count_inversions() {
+        assert_eq!(count_inversions::<i32>(&[]), 0);
+        assert_eq!(count_inversions(&[1]), 0);
+        assert_eq!(count_inversions(&[1,2,3,4]), 0);
+        assert_eq!(count_inversions(&[4,3,2,1]), 6);
+        assert_eq!(count_inversions(&[2,4,1,3,5]), 3);
+        assert_eq!(count_inversions(&[1,1,1]), 0);
+        assert_eq!(count_inversions(&[2,1,2,1]), 3);
+
+        // compare with the quadratic definition
+        let v: Vec<u32> = (0..300).map(|i| (i * 7919) % 101).collect();
+        let mut expected = 0;
+        for i in 0..v.len() {
+            for j in i+1..v.len() {
+                if v[i] > v[j] {
+                    expected += 1;
+                }
+            }
+        }
+        assert_eq!(count_inversions(&v), expected);
+    }
+
+    #[test]
+    fn test_count_runs() {
+        assert_eq!(count_runs::<i32>(&[]), 0);
+        assert_eq!(count_runs(&[1]), 1);
+        assert_eq!(count_runs(&[1,1,2,2]), 1);
+        assert_eq!(count_runs(&[1,2,3,2,5,4]), 3);
+        assert_eq!(count_runs(&[5,4,3,2,1]), 5);
+    }
+
+    #[test]
+    fn test_longest_increasing_subsequence() {
+        assert_eq!(longest_increasing_subsequence::<i32>(&[]), 0);
+        assert_eq!(longest_increasing_subsequence(&[1]), 1);
+        assert_eq!(longest_increasing_subsequence(&[5,4,3,2,1]), 1);
+        assert_eq!(longest_increasing_subsequence(&[2,2,2]), 3);
+        assert_eq!(longest_increasing_subsequence(&[3,1,4,1,5,9,2,6,5,3,5]), 5);
+
+        assert_eq!(count_removals(&[1,2,3]), 0);
+        assert_eq!(count_removals(&[1,5,2,3,4]), 1);
+    }
+}
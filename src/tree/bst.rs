@@ -221,11 +221,18 @@ impl<T: Ord + Copy> BST<T> {
         }
     }
 
+    // builder style insert, see add
     pub fn insert(mut self, data: T) -> Self {
-        self.root = insert(self.root, data);
+        self.add(data);
         self
     }
 
+    // returns whether data was inserted, which is always true as the tree keeps duplicates
+    pub fn add(&mut self, data: T) -> bool {
+        self.root = insert(self.root.take(), data);
+        true
+    }
+
     pub fn search(&self, target: T) -> bool {
         match search(&self.root, target) {
             Some(_) => true,
@@ -258,16 +265,31 @@ impl<T: Ord + Copy> BST<T> {
         }
     }
 
+    // builder style delete, see remove
     pub fn delete(mut self, target: T) -> Self {
-        self.root = delete(self.root, target);
+        self.remove(target);
         self
     }
 
+    // builder style delete_min, see remove_min
     pub fn delete_min(mut self) -> Self {
-        self.root = delete_successor(self.root);
+        self.remove_min();
         self
     }
 
+    // remove one copy of target, returns whether target was found
+    pub fn remove(&mut self, target: T) -> bool {
+        let size = self.size();
+        self.root = delete(self.root.take(), target);
+        self.size() < size
+    }
+
+    pub fn remove_min(&mut self) -> Option<T> {
+        let min = self.min().copied();
+        self.root = delete_successor(self.root.take());
+        min
+    }
+
     pub fn iter(&self) -> Iter<'_, T> {
         Iter::new(self)
     }
@@ -432,4 +454,31 @@ mod tests {
         t = t.insert(1).insert(1).insert(1).insert(1);
         assert_eq!(t.size(), 5);
     }
+
+    #[test]
+    fn test_mutable_operation() {
+        struct Holder {
+            t: BST<i32>,
+        }
+
+        let mut h = Holder { t: BST::new() };
+        for &x in [3, 1, 4, 1, 5].iter() {
+            assert!(h.t.add(x));
+        }
+        assert_eq!(h.t.size(), 5);
+        assert_eq!(h.t.iter().cloned().collect::<Vec<_>>(), vec![1, 1, 3, 4, 5]);
+
+        assert!(h.t.remove(1));
+        assert!(h.t.search(1));
+        assert!(h.t.remove(1));
+        assert!(!h.t.remove(1));
+        assert!(!h.t.remove(9));
+        assert_eq!(h.t.size(), 3);
+
+        assert_eq!(h.t.remove_min(), Some(3));
+        assert_eq!(h.t.remove_min(), Some(4));
+        assert_eq!(h.t.remove_min(), Some(5));
+        assert_eq!(h.t.remove_min(), None);
+        assert_eq!(h.t.size(), 0);
+    }
 }
@@ -0,0 +1,267 @@
+use std::cmp::Ordering::{Less, Greater, Equal};
+use std::mem;
+
+// an ordered map on a binary search tree: the keys satisfy the binary search tree property
+// and each key is stored with its value. unlike BST, the keys are unique and neither the
+// keys nor the values need to be Copy.
+
+// inspired by https://algs4.cs.princeton.edu/32bst/BST.java.html
+
+////////////////////////////////////////////////////////////////////////////////
+// internal tree node
+////////////////////////////////////////////////////////////////////////////////
+struct Node<K, V> {
+    key: K,
+    value: V,
+    size: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+impl<K, V> Node<K, V> {
+    fn new(key: K, value: V) -> Self {
+        Self {
+            key,
+            value,
+            size: 1,
+            left: None,
+            right: None,
+        }
+    }
+}
+
+fn size<K, V>(node: &Link<K, V>) -> usize {
+    match node {
+        Some(node_box) => node_box.size,
+        None => 0,
+    }
+}
+
+// returns the old value if key was present
+fn insert<K: Ord, V>(node: &mut Link<K, V>, key: K, value: V) -> Option<V> {
+    match node {
+        Some(node_box) => {
+            let old = match key.cmp(&node_box.key) {
+                Less => insert(&mut node_box.left, key, value),
+                Greater => insert(&mut node_box.right, key, value),
+                Equal => return Some(mem::replace(&mut node_box.value, value)),
+            };
+
+            if old.is_none() {
+                node_box.size += 1;
+            }
+            old
+        },
+        None => {
+            *node = Some(Box::new(Node::new(key, value)));
+            None
+        },
+    }
+}
+
+// detach the node with the smallest key
+fn remove_min<K, V>(node: &mut Link<K, V>) -> Link<K, V> {
+    match node {
+        Some(node_box) if node_box.left.is_some() => {
+            node_box.size -= 1;
+            remove_min(&mut node_box.left)
+        },
+        _ => {
+            let mut min = node.take()?;
+            *node = min.right.take();
+            Some(min)
+        },
+    }
+}
+
+// returns the value of key if it was present
+fn remove<K: Ord, V>(node: &mut Link<K, V>, key: &K) -> Option<V> {
+    let node_box = node.as_mut()?;
+    let removed = match key.cmp(&node_box.key) {
+        Less => remove(&mut node_box.left, key),
+        Greater => remove(&mut node_box.right, key),
+        Equal => {
+            let mut target = node.take().unwrap();
+            *node = match (target.left.take(), target.right.take()) {
+                (None, None) => None,
+                (sub_tree @ Some(_), None) | (None, sub_tree @ Some(_)) => sub_tree,
+                (left, mut right) => {
+                    // replace target with its successor, the min of the right sub tree
+                    let mut successor = remove_min(&mut right).unwrap();
+                    successor.left = left;
+                    successor.right = right;
+                    successor.size = target.size - 1;
+                    Some(successor)
+                },
+            };
+            return Some(target.value);
+        },
+    };
+
+    if removed.is_some() {
+        node_box.size -= 1;
+    }
+    removed
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// exposed BSTMap type
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct BSTMap<K: Ord, V> {
+    root: Link<K, V>,
+}
+
+impl<K: Ord, V> BSTMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+        }
+    }
+
+    // returns the old value if key was present, the key itself is not updated
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        insert(&mut self.root, key, value)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut node = &self.root;
+        while let Some(node_box) = node {
+            match key.cmp(&node_box.key) {
+                Less => node = &node_box.left,
+                Greater => node = &node_box.right,
+                Equal => return Some(&node_box.value),
+            }
+        }
+        None
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        while let Some(node_box) = node {
+            match key.cmp(&node_box.key) {
+                Less => node = &mut node_box.left,
+                Greater => node = &mut node_box.right,
+                Equal => return Some(&mut node_box.value),
+            }
+        }
+        None
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        remove(&mut self.root, key)
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    // in ascending order of keys
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left(&self.root);
+        iter
+    }
+}
+
+impl<K: Ord, V> Default for BSTMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// iterators
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    // push node and all the nodes on its left spine onto the stack
+    fn push_left(&mut self, mut node: &'a Link<K, V>) {
+        while let Some(node_box) = node {
+            self.stack.push(node_box);
+            node = &node_box.left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        Some((&node.key, &node.value))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_basic_operation() {
+        let mut m = BSTMap::new();
+        assert!(m.is_empty());
+        assert_eq!(m.insert(String::from("b"), vec![2]), None);
+        assert_eq!(m.insert(String::from("a"), vec![1]), None);
+        assert_eq!(m.insert(String::from("c"), vec![3]), None);
+        assert_eq!(m.insert(String::from("b"), vec![2, 2]), Some(vec![2]));
+        assert_eq!(m.len(), 3);
+
+        assert_eq!(m.get(&String::from("b")), Some(&vec![2, 2]));
+        assert!(m.contains_key(&String::from("a")));
+        assert!(!m.contains_key(&String::from("d")));
+
+        m.get_mut(&String::from("a")).unwrap().push(1);
+        let pairs: Vec<_> = m.iter().map(|(k, v)| (k.as_str(), v.len())).collect();
+        assert_eq!(pairs, vec![("a", 2), ("b", 2), ("c", 1)]);
+
+        assert_eq!(m.remove(&String::from("b")), Some(vec![2, 2]));
+        assert_eq!(m.remove(&String::from("b")), None);
+        assert_eq!(m.get_mut(&String::from("b")), None);
+        assert_eq!(m.len(), 2);
+    }
+
+    #[test]
+    fn test_against_btree_map() {
+        let mut gen = rand::thread_rng();
+        let mut m = BSTMap::new();
+        let mut expected = BTreeMap::new();
+
+        for i in 0..2000 {
+            let key: u32 = gen.gen_range(0, 200);
+            if gen.gen_range(0, 3) == 0 {
+                assert_eq!(m.remove(&key), expected.remove(&key));
+            } else {
+                assert_eq!(m.insert(key, i), expected.insert(key, i));
+            }
+            assert_eq!(m.len(), expected.len());
+        }
+
+        assert!(m.iter().eq(expected.iter()));
+        for key in 0..200 {
+            assert_eq!(m.get(&key), expected.get(&key));
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod bst;
+pub mod bst_map;
 pub mod segment_tree;
 pub mod treap;
 pub mod implict_treap;
\ No newline at end of file